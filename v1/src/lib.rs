@@ -1,162 +1,4499 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
-use frame_support::{decl_error, decl_event, decl_module, decl_storage, dispatch::DispatchResult, ensure};
-use frame_system::ensure_signed;
-use sp_std::vec::Vec;
+use frame_support::{
+	decl_error, decl_event, decl_module, decl_storage,
+	dispatch::{DispatchError, DispatchResult, DispatchResultWithPostInfo},
+	ensure, storage::{unhashed, with_transaction, StoragePrefixedMap},
+	traits::{Currency, ExistenceRequirement, Get, OnKilledAccount, ReservableCurrency},
+	transactional, weights::Weight, IterableStorageDoubleMap, IterableStorageMap,
+};
+use frame_system::{ensure_root, ensure_signed};
+use parity_scale_codec::{Decode, Encode};
+use sp_runtime::{
+	traits::{AccountIdConversion, AtLeast32BitUnsigned, CheckedAdd, SaturatedConversion, Saturating, StaticLookup, Zero},
+	ModuleId, RuntimeDebug, TransactionOutcome,
+};
+use sp_std::{convert::TryInto, vec::Vec};
+
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
 
 pub trait Config: frame_system::Config {
 	type Event: From<Event<Self>> + Into<<Self as frame_system::Config>::Event>;
+
+	//Largest number of decimals a token may be minted with
+	type MaxDecimals: Get<u8>;
+
+	//Number of Balances entries a single audit_supply call visits
+	type AuditBatchSize: Get<u32>;
+
+	//Where the tokens of a reaped account are swept to; they are burned when this is None
+	type DormantDestination: Get<Option<Self::AccountId>>;
+
+	//Largest number of allowances removed in one go when their owner is reaped
+	type MaxAllowanceRemovals: Get<u32>;
+
+	//Blocks before a new or increased allowance can be spent; zero makes it spendable at once
+	type ApprovalDelay: Get<Self::BlockNumber>;
+
+	//Largest number of items in a batch call
+	type MaxBatchSize: Get<u32>;
+
+	//Longest memo, in bytes, that can be attached to a transfer
+	type MaxMemoLength: Get<u32>;
+
+	//Blocks a transfer_idempotent reference is remembered for
+	type ReferenceRetention: Get<Self::BlockNumber>;
+
+	//Largest number of transfer_idempotent references recorded in one block
+	type MaxReferencesPerBlock: Get<u32>;
+
+	//Longest token URI, in bytes
+	type MaxUriLength: Get<u32>;
+
+	//Identifier the pallet's own account is derived from
+	type ModuleId: Get<ModuleId>;
+
+	//Blocks before a loosened or removed transfer limit takes effect
+	type TransferLimitDelay: Get<Self::BlockNumber>;
+
+	//Largest number of locks an account can carry at once
+	type MaxLocks: Get<u32>;
+
+	//Largest number of vesting schedules running on one account
+	type MaxVestingSchedules: Get<u32>;
+
+	//Smallest amount a vested_transfer can lock into a new schedule
+	type MinVestedTransfer: Get<u64>;
+
+	//Smallest amount a transfer_locked can lock
+	type MinLockedTransfer: Get<u64>;
+
+	//Largest number of scheduled transfers executed in one block
+	type MaxScheduledPerBlock: Get<u32>;
+
+	//Voting power checkpoints kept per account; older ones are dropped
+	type MaxCheckpoints: Get<u32>;
+
+	//Blocks unstaked tokens wait before they can be withdrawn; zero pays them out at once
+	type UnbondingPeriod: Get<Self::BlockNumber>;
+
+	//Blocks a conviction lock lasts per conviction level
+	type LockPeriod: Get<Self::BlockNumber>;
+
+	//Largest number of balances loaded by one force_import_balances call
+	type MaxImportChunk: Get<u32>;
+
+	//Largest number of recipients in one airdrop
+	type MaxAirdropSize: Get<u32>;
+
+	//Native currency the crowdsale is paid in and the token wraps
+	type Currency: ReservableCurrency<Self::AccountId>;
+
+	//Native currency reserved from whoever touches an account, until the account is empty again
+	type AccountDeposit: Get<BalanceOf<Self>>;
+}
+
+pub type BalanceOf<T> = <<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+
+//Lets other pallets lock tokens of this pallet without going through extrinsics, in the
+//way LockableCurrency is used for the native currency. Locked tokens cannot be transferred
+//or burned by their holder.
+pub trait TokenLocks<AccountId, BlockNumber> {
+	//Creates or replaces lock 'id' on 'who'
+	fn set_lock(id: LockIdentifier, who: &AccountId, amount: u64, until: Option<BlockNumber>) -> DispatchResult;
+
+	//Like set_lock, but never lowers the amount or shortens the duration of an existing lock
+	fn extend_lock(id: LockIdentifier, who: &AccountId, amount: u64, until: Option<BlockNumber>) -> DispatchResult;
+
+	fn remove_lock(id: LockIdentifier, who: &AccountId);
+
+	//Balance of 'who' that cannot currently move
+	fn locked_balance(who: &AccountId) -> u64;
+}
+
+//An allowance of this size is never decremented by spends
+pub const INFINITE_ALLOWANCE: u64 = u64::MAX;
+
+//Ceiling on the amount spent within a window of blocks
+#[derive(Encode, Decode, Clone, PartialEq, Eq, Default, RuntimeDebug)]
+pub struct RateLimit<BlockNumber> {
+	//Most that can be spent within one window
+	pub max_per_period: u64,
+	//Length of a window in blocks
+	pub period_blocks: BlockNumber,
+	//First block of the current window
+	pub window_start: BlockNumber,
+	//Amount spent since window_start
+	pub spent: u64,
+}
+
+impl<BlockNumber: AtLeast32BitUnsigned + Copy> RateLimit<BlockNumber> {
+	//A limit whose first window starts at 'now'
+	pub fn new(max_per_period: u64, period_blocks: BlockNumber, now: BlockNumber) -> Self {
+		RateLimit { max_per_period, period_blocks, window_start: now, spent: 0 }
+	}
+
+	//Records 'amount' as spent at block 'now', starting a new window once the current
+	//one has elapsed. Returns false, leaving the limit as it was, if the ceiling would be exceeded.
+	pub fn try_spend(&mut self, now: BlockNumber, amount: u64) -> bool {
+		let (window_start, spent) = if now >= self.window_start.saturating_add(self.period_blocks) {
+			(now, 0)
+		} else {
+			(self.window_start, self.spent)
+		};
+		match spent.checked_add(amount) {
+			Some(spent) if spent <= self.max_per_period => {
+				self.window_start = window_start;
+				self.spent = spent;
+				true
+			}
+			_ => false,
+		}
+	}
+}
+
+//An allowance together with the terms it was granted under
+#[derive(Encode, Decode, Clone, PartialEq, Eq, Default, RuntimeDebug)]
+pub struct Allowance<AccountId, BlockNumber> {
+	//Amount the spender may still withdraw
+	pub amount: u64,
+	//Last block at which the allowance can be spent; None never expires
+	pub expires_at: Option<BlockNumber>,
+	//Number of transfer_from calls left before the allowance is removed; None is unlimited
+	pub uses_left: Option<u32>,
+	//Only account the allowance may be spent towards; None allows any recipient
+	pub allowed_dest: Option<AccountId>,
+	//Ceiling on how fast the allowance can be drained; None is unlimited
+	pub rate_limit: Option<RateLimit<BlockNumber>>,
+	//First block at which the allowance can be spent
+	pub active_from: BlockNumber,
+}
+
+impl<AccountId, BlockNumber: Default> Allowance<AccountId, BlockNumber> {
+	//A plain allowance with no further restrictions
+	pub fn new(amount: u64) -> Self {
+		Allowance {
+			amount,
+			expires_at: None,
+			uses_left: None,
+			allowed_dest: None,
+			rate_limit: None,
+			active_from: Default::default(),
+		}
+	}
+}
+
+pub type AllowanceOf<T> = Allowance<<T as frame_system::Config>::AccountId, <T as frame_system::Config>::BlockNumber>;
+
+//Issuance rights the owner granted to a minter
+#[derive(Encode, Decode, Clone, PartialEq, Eq, Default, RuntimeDebug)]
+pub struct MinterQuota<BlockNumber> {
+	//Tokens the minter may still issue
+	pub remaining: u64,
+	//Last block at which the minter may issue
+	pub valid_until: BlockNumber,
+}
+
+//Identifier of a lock, so several reasons can lock the same balance independently
+pub type LockIdentifier = [u8; 8];
+
+//Part of a balance that cannot be transferred or burned
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct BalanceLock<BlockNumber> {
+	pub id: LockIdentifier,
+	//Balance that has to stay in the account
+	pub amount: u64,
+	//Block from which the lock no longer applies; None locks until removed
+	pub until: Option<BlockNumber>,
+}
+
+impl<BlockNumber: PartialOrd> BalanceLock<BlockNumber> {
+	pub fn is_active(&self, now: &BlockNumber) -> bool {
+		self.until.as_ref().map_or(true, |until| now < until)
+	}
+}
+
+//Lock id under which unvested tokens are held
+pub const VESTING_ID: LockIdentifier = *b"vesting ";
+
+//Lock id under which conviction-locked tokens are held
+pub const CONVICTION_ID: LockIdentifier = *b"convictn";
+
+//Highest conviction level; a level is the number of LockPeriods the tokens stay locked
+pub const MAX_CONVICTION: u8 = 6;
+
+//Tokens locked for extra voting weight until 'unlock_at'
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct ConvictionLock<BlockNumber> {
+	pub amount: u64,
+	pub periods: u8,
+	pub unlock_at: BlockNumber,
+	//Votes added on top of the balance, in shares: the locked shares times 'periods'
+	pub boost: u64,
+}
+
+//Prefix of the lock ids holding transfer_locked tokens; the rest of the id is the unlock block
+pub const TIME_LOCK_PREFIX: [u8; 2] = *b"tl";
+
+//Tokens released linearly, 'per_block' at a time, from 'starting_block' on
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct VestingSchedule<BlockNumber> {
+	//Tokens covered by the schedule
+	pub locked: u64,
+	pub per_block: u64,
+	pub starting_block: BlockNumber,
+}
+
+impl<BlockNumber: AtLeast32BitUnsigned + Copy> VestingSchedule<BlockNumber> {
+	//Tokens still unvested at block 'now'
+	pub fn locked_at(&self, now: BlockNumber) -> u64 {
+		let elapsed: u64 = now.saturating_sub(self.starting_block).saturated_into();
+		self.locked.saturating_sub(self.per_block.saturating_mul(elapsed))
+	}
+}
+
+//Tokens held by the pallet account until the beneficiary claims them or the deadline passes
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct Escrow<AccountId, BlockNumber> {
+	pub creator: AccountId,
+	pub beneficiary: AccountId,
+	pub value: u64,
+	//Last block the beneficiary can claim at; the creator can take the tokens back afterwards
+	pub deadline: BlockNumber,
+}
+
+pub type EscrowOf<T> = Escrow<<T as frame_system::Config>::AccountId, <T as frame_system::Config>::BlockNumber>;
+
+//Transfer queued to run at the start of block 'at', its value reserved until then
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct ScheduledTransfer<AccountId, BlockNumber> {
+	pub from: AccountId,
+	pub dest: AccountId,
+	pub value: u64,
+	pub at: BlockNumber,
+}
+
+pub type ScheduledTransferOf<T> = ScheduledTransfer<<T as frame_system::Config>::AccountId, <T as frame_system::Config>::BlockNumber>;
+
+//Standing permission for a merchant to pull a fixed amount once per period
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct Subscription<AccountId, BlockNumber> {
+	pub merchant: AccountId,
+	pub amount_per_period: u64,
+	pub period_blocks: BlockNumber,
+	//Periods after which the subscription ends; None runs until cancelled
+	pub max_periods: Option<u32>,
+	//Periods collected so far
+	pub collected: u32,
+	//First block at which the next period can be collected
+	pub next_due: BlockNumber,
+}
+
+pub type SubscriptionOf<T> = Subscription<<T as frame_system::Config>::AccountId, <T as frame_system::Config>::BlockNumber>;
+
+//Tokens held by the pallet account that accrue to the recipient block by block
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct Stream<AccountId, BlockNumber> {
+	pub sender: AccountId,
+	pub recipient: AccountId,
+	pub total: u64,
+	//Already paid out to the recipient
+	pub withdrawn: u64,
+	pub start_block: BlockNumber,
+	//Block by which the whole total has streamed
+	pub end_block: BlockNumber,
+}
+
+impl<AccountId, BlockNumber: AtLeast32BitUnsigned + Copy> Stream<AccountId, BlockNumber> {
+	//Total streamed to the recipient by block 'now'. Computed from the start every time,
+	//so rounding never loses tokens and the full total has streamed at end_block.
+	pub fn accrued_at(&self, now: BlockNumber) -> u64 {
+		if now >= self.end_block {
+			return self.total;
+		}
+		let elapsed: u128 = now.saturating_sub(self.start_block).saturated_into();
+		let duration: u128 = self.end_block.saturating_sub(self.start_block).saturated_into();
+		(self.total as u128 * elapsed / duration) as u64
+	}
+}
+
+pub type StreamOf<T> = Stream<<T as frame_system::Config>::AccountId, <T as frame_system::Config>::BlockNumber>;
+
+//Payment request waiting to be paid to its creator
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct Invoice<AccountId, BlockNumber> {
+	pub creator: AccountId,
+	//Only this account may pay; None lets anyone pay
+	pub payer: Option<AccountId>,
+	pub amount: u64,
+	pub memo: Vec<u8>,
+	//Last block the invoice can be paid at
+	pub expires_at: BlockNumber,
+}
+
+pub type InvoiceOf<T> = Invoice<<T as frame_system::Config>::AccountId, <T as frame_system::Config>::BlockNumber>;
+
+//Crowdsale selling tokens for native currency at a fixed rate
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct Sale<Balance, BlockNumber> {
+	//Tokens credited per unit of native currency
+	pub rate: u64,
+	//Native currency that must be raised by end_block, or contributions are refunded
+	pub soft_cap: Balance,
+	//Most native currency the sale accepts
+	pub hard_cap: Balance,
+	pub start_block: BlockNumber,
+	pub end_block: BlockNumber,
+	//Native currency contributed and not refunded
+	pub raised: Balance,
+	pub finalized: bool,
+}
+
+impl<Balance: AtLeast32BitUnsigned + Copy, BlockNumber: PartialOrd> Sale<Balance, BlockNumber> {
+	pub fn is_open(&self, now: &BlockNumber) -> bool {
+		!self.finalized && *now >= self.start_block && *now <= self.end_block && self.raised < self.hard_cap
+	}
+
+	pub fn has_ended(&self, now: &BlockNumber) -> bool {
+		*now > self.end_block || self.raised >= self.hard_cap
+	}
+}
+
+pub type SaleOf<T> = Sale<BalanceOf<T>, <T as frame_system::Config>::BlockNumber>;
+
+//Native currency an account keeps reserved against the tokens it minted with it
+#[derive(Encode, Decode, Clone, PartialEq, Eq, Default, RuntimeDebug)]
+pub struct CollateralPosition<Balance> {
+	pub collateral: Balance,
+	//Tokens minted against the collateral and not redeemed yet
+	pub minted: u64,
+	//Collateral needed per token, in basis points, as configured when the position was opened
+	pub ratio_bps: u32,
+}
+
+impl<Balance: AtLeast32BitUnsigned + Copy> CollateralPosition<Balance> {
+	//Collateral that backs 'tokens' at the ratio of this position, rounded up
+	pub fn collateral_for(&self, tokens: u64) -> Balance {
+		let required = (tokens as u128 * self.ratio_bps as u128 + 9_999) / 10_000;
+		required.saturated_into()
+	}
+
+	//Share of the collateral released when 'tokens' of the minted ones are redeemed
+	pub fn release_for(&self, tokens: u64) -> Balance {
+		if tokens >= self.minted {
+			return self.collateral;
+		}
+		let collateral: u128 = self.collateral.saturated_into();
+		(collateral * tokens as u128 / self.minted as u128).saturated_into()
+	}
+}
+
+pub type CollateralPositionOf<T> = CollateralPosition<BalanceOf<T>>;
+
+//Offer to sell reserved tokens to one counterparty for native currency
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct Swap<AccountId, Balance, BlockNumber> {
+	pub creator: AccountId,
+	pub counterparty: AccountId,
+	//Tokens reserved from the creator until the swap is accepted or cancelled
+	pub token_amount: u64,
+	//Native currency the counterparty pays for them
+	pub native_amount: Balance,
+	//Last block the swap can be accepted at
+	pub expires_at: BlockNumber,
+}
+
+pub type SwapOf<T> = Swap<<T as frame_system::Config>::AccountId, BalanceOf<T>, <T as frame_system::Config>::BlockNumber>;
+
+//Ethereum address, the last 20 bytes of the keccak-256 hash of a public key
+pub type EthereumAddress = [u8; 20];
+
+//Text that claim_eth signatures start with, ahead of the claiming account
+pub const ETH_CLAIM_PREFIX: &[u8] = b"Claim ERC20 tokens to the account:";
+
+//Tokens held for the accounts listed in a merkle tree of (account, amount) leaves
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct ClaimDrop<AccountId, BlockNumber> {
+	pub creator: AccountId,
+	pub merkle_root: [u8; 32],
+	//Tokens funded and not claimed yet
+	pub remaining: u64,
+	//Last block claims are taken at; the creator can sweep what is left afterwards
+	pub expiry: BlockNumber,
+}
+
+pub type ClaimDropOf<T> = ClaimDrop<<T as frame_system::Config>::AccountId, <T as frame_system::Config>::BlockNumber>;
+
+//Testnet faucet handing out 'drip_amount' of the tokens of 'source'
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct FaucetConfig<AccountId, BlockNumber> {
+	pub drip_amount: u64,
+	//Blocks an account waits between two drips
+	pub cooldown_blocks: BlockNumber,
+	//Pays the drips out of the allowance it granted to the pallet account
+	pub source: AccountId,
+}
+
+pub type FaucetConfigOf<T> = FaucetConfig<<T as frame_system::Config>::AccountId, <T as frame_system::Config>::BlockNumber>;
+
+//Scale of ScalingFactor; a factor of SCALING_PRECISION shows every share as one token
+pub const SCALING_PRECISION: u128 = 1_000_000_000_000;
+
+//Scale of RewardPerShare, so rewards per staked token keep 12 decimals of precision
+pub const REWARD_PRECISION: u128 = 1_000_000_000_000;
+
+//Scale of MagnifiedDividendPerShare, so dividends on small holdings are not rounded away
+pub const DIVIDEND_MAGNITUDE: u128 = 1 << 64;
+
+//Tokens an account has staked, with the rewards per share it has already been credited for
+#[derive(Encode, Decode, Clone, PartialEq, Eq, Default, RuntimeDebug)]
+pub struct StakeInfo {
+	pub amount: u64,
+	//amount * RewardPerShare at the last claim, scaled by REWARD_PRECISION
+	pub reward_debt: u128,
+}
+
+//Fixed per-block issuance paid to a beneficiary
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct InflationConfig<AccountId, BlockNumber> {
+	//Tokens issued at the start of every block
+	pub per_block: u64,
+	//Account the new tokens are credited to
+	pub beneficiary: AccountId,
+	//Last block that pays out; None keeps going until the cap is reached
+	pub end_block: Option<BlockNumber>,
+}
+
+pub type InflationConfigOf<T> = InflationConfig<<T as frame_system::Config>::AccountId, <T as frame_system::Config>::BlockNumber>;
+
+//Share of every transfer paid to the treasury account
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct TransferFee {
+	//Fee in basis points of the transferred value, at most 10_000
+	pub rate_bps: u16,
+}
+
+impl TransferFee {
+	//Fee owed on a transfer of 'value', rounded down
+	pub fn fee_on(&self, value: u64) -> u64 {
+		(value as u128 * self.rate_bps as u128 / 10_000) as u64
+	}
+}
+
+//Failure behaviour of batch calls
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+pub enum BatchMode {
+	//Any failing item aborts the whole batch
+	Atomic,
+	//Failing items are skipped and reported, the rest go through
+	BestEffort,
 }
 
+//Narrow permissions the owner can hand out. An account's roles are stored together as a
+//bit set, see Role::bit.
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+pub enum Role {
+	//May call mint_to
+	Minter,
+	//May pause and unpause transfers
+	Pauser,
+	//May freeze and thaw accounts
+	Freezer,
+}
+
+impl Role {
+	pub fn bit(self) -> u8 {
+		1 << (self as u8)
+	}
+}
+
+//What happens to a balance left below MinBalance
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+pub enum DustPolicy {
+	//Credited to the treasury account
+	Treasury,
+	//Destroyed, lowering TotalIssuance
+	Burn,
+}
+
+impl Default for DustPolicy {
+	fn default() -> Self {
+		DustPolicy::Treasury
+	}
+}
+
+//Who may take part in transfers
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+pub enum TransferPolicy {
+	//Anyone
+	Open,
+	//Only whitelisted senders to whitelisted recipients
+	WhitelistOnly,
+}
+
+impl Default for TransferPolicy {
+	fn default() -> Self {
+		TransferPolicy::Open
+	}
+}
+
+//Storage layout versions, used to run each migration exactly once
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, RuntimeDebug)]
+pub enum Releases {
+	//Balances were held without a provider reference on the account
+	V1,
+	//Every Balances entry holds one provider reference
+	V2,
+	//Allowances store an Allowance record instead of a bare amount
+	V3,
+	//Every holder has a voting power checkpoint for its balance
+	V4,
+	//Balances, reserves and TotalIssuance count shares, shown as tokens through ScalingFactor
+	V5,
+	//Transfer fees go to the treasury account instead of a configured collector
+	V6,
+	//TotalIssuance and TotalReserved are recomputed from the stored balances and reserves
+	V7,
+}
+
+impl Default for Releases {
+	fn default() -> Self {
+		Releases::V1
+	}
+}
+
+// Amount parameters of dispatchable calls are SCALE compact encoded, matching
+// pallet_balances. Calls encoded with the earlier fixed-width u64 amounts no longer
+// decode, so runtimes upgrading past this change must bump `transaction_version`.
 decl_module! {
 	pub struct Module<T: Config> for enum Call where origin: T::Origin {
 		fn deposit_event() = default;
 		type Error = MyError<T>;
 
-		//Generate token
+		const MaxDecimals: u8 = T::MaxDecimals::get();
+		const AuditBatchSize: u32 = T::AuditBatchSize::get();
+		const MaxAllowanceRemovals: u32 = T::MaxAllowanceRemovals::get();
+		const ApprovalDelay: T::BlockNumber = T::ApprovalDelay::get();
+		const MaxBatchSize: u32 = T::MaxBatchSize::get();
+		const MaxMemoLength: u32 = T::MaxMemoLength::get();
+		const ReferenceRetention: T::BlockNumber = T::ReferenceRetention::get();
+		const MaxReferencesPerBlock: u32 = T::MaxReferencesPerBlock::get();
+		const MaxUriLength: u32 = T::MaxUriLength::get();
+		const ModuleId: ModuleId = T::ModuleId::get();
+		const TransferLimitDelay: T::BlockNumber = T::TransferLimitDelay::get();
+		const MaxLocks: u32 = T::MaxLocks::get();
+		const MaxVestingSchedules: u32 = T::MaxVestingSchedules::get();
+		const MinVestedTransfer: u64 = T::MinVestedTransfer::get();
+		const MinLockedTransfer: u64 = T::MinLockedTransfer::get();
+		const MaxScheduledPerBlock: u32 = T::MaxScheduledPerBlock::get();
+		const MaxCheckpoints: u32 = T::MaxCheckpoints::get();
+		const UnbondingPeriod: T::BlockNumber = T::UnbondingPeriod::get();
+		const MaxAirdropSize: u32 = T::MaxAirdropSize::get();
+		const MaxImportChunk: u32 = T::MaxImportChunk::get();
+		const LockPeriod: T::BlockNumber = T::LockPeriod::get();
+		const AccountDeposit: BalanceOf<T> = T::AccountDeposit::get();
+
+		fn on_initialize(now: T::BlockNumber) -> Weight {
+			BlockVolume::kill();
+			T::DbWeight::get().writes(1)
+				.saturating_add(Self::apply_inflation(now))
+				.saturating_add(Self::prune_references(now))
+				.saturating_add(Self::execute_scheduled(now))
+		}
+
+		fn on_runtime_upgrade() -> Weight {
+			let mut weight = T::DbWeight::get().reads(1);
+			if Self::storage_version() < Releases::V2 {
+				weight = weight.saturating_add(Self::migrate_to_v2());
+			}
+			if Self::storage_version() < Releases::V3 {
+				weight = weight.saturating_add(Self::migrate_to_v3());
+			}
+			if Self::storage_version() < Releases::V4 {
+				weight = weight.saturating_add(Self::migrate_to_v4());
+			}
+			if Self::storage_version() < Releases::V5 {
+				weight = weight.saturating_add(Self::migrate_to_v5());
+			}
+			if Self::storage_version() < Releases::V6 {
+				weight = weight.saturating_add(Self::migrate_to_v6());
+			}
+			if Self::storage_version() < Releases::V7 {
+				weight = weight.saturating_add(Self::migrate_to_v7());
+			}
+			weight
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade() -> Result<(), &'static str> {
+			Self::do_try_state()
+		}
+
+		//Generate token with a cap of 'supply', crediting 'initial' of it to the creator, who
+		//becomes the token owner. The rest can be issued later through mint_to. Transfers may
+		//not create accounts holding less than 'min_balance'.
 		#[weight = 10_000]
-		fn mint(origin, name: Vec<u8>, ticker: Vec<u8>, supply: u64, decimals: u8) -> DispatchResult {
+		fn mint(
+			origin,
+			name: Vec<u8>,
+			ticker: Vec<u8>,
+			#[compact] supply: u64,
+			#[compact] initial: u64,
+			decimals: u8,
+			#[compact] min_balance: u64,
+		) -> DispatchResult {
+			Self::ensure_not_halted()?;
+			let creator = ensure_signed(origin)?;
+			ensure!(initial <= supply, MyError::<T>::CapExceeded);
+			let allocations = if initial > 0 { sp_std::vec![(creator.clone(), initial)] } else { Vec::new() };
+			Self::do_mint(creator, name, ticker, supply, decimals, min_balance, &allocations)
+		}
+
+		//Generate token with a cap of 'supply' and split all of it between 'allocations' at
+		//once, for instance between treasury, team and community. The allocations must add up
+		//to 'supply'. The creator becomes the token owner.
+		#[weight = 10_000 * (allocations.len().min(T::MaxBatchSize::get() as usize) as u64 + 1)]
+		fn mint_with_distribution(
+			origin,
+			name: Vec<u8>,
+			ticker: Vec<u8>,
+			#[compact] supply: u64,
+			allocations: Vec<(T::AccountId, u64)>,
+			decimals: u8,
+			#[compact] min_balance: u64,
+		) -> DispatchResult {
+			Self::ensure_not_halted()?;
 			let creator = ensure_signed(origin)?;
-			ensure!(name.len() <= 64, MyError::<T>::NameTooBig);
-			ensure!(ticker.len() <= 32, MyError::<T>::TickerTooBig);
-			ensure!(Self::get_mint() == false, MyError::<T>::AlreadyMinted);
-			
-			Ticker::put(ticker);
-			Name::put(name);
-			MaxSupply::put(supply);
-			Decimals::put(decimals);	
+			ensure!(!allocations.is_empty(), MyError::<T>::EmptyBatch);
+			ensure!(allocations.len() <= T::MaxBatchSize::get() as usize, MyError::<T>::BatchTooLarge);
+			let total = allocations.iter().try_fold(0u64, |total, (_, amount)| total.checked_add(*amount));
+			ensure!(total == Some(supply), MyError::<T>::DistributionMismatch);
+			Self::do_mint(creator, name, ticker, supply, decimals, min_balance, &allocations)
+		}
+
+		//Starts a balance import, during which transfers are blocked. Root only.
+		#[weight = 10_000]
+		fn open_import(origin) -> DispatchResult {
+			Self::ensure_not_halted()?;
+			ensure_root(origin)?;
+			ensure!(!Self::import_open(), MyError::<T>::ImportIsOpen);
+
+			ImportOpen::put(true);
+			ImportedTotal::kill();
+			Self::deposit_event(RawEvent::ImportOpened);
+			Ok(())
+		}
 
-			<Balances<T>>::insert(creator, supply);
-			Minted::put(true);
+		//Loads one chunk of balances while the import is open. Accounts must not hold a balance
+		//yet nor repeat within the chunk, and the chunk must fit under MaxSupply; otherwise
+		//none of it is loaded. Root only.
+		#[weight = 10_000 * (entries.len().min(T::MaxImportChunk::get() as usize) as u64 + 1)]
+		#[transactional]
+		fn force_import_balances(origin, entries: Vec<(T::AccountId, u64)>) -> DispatchResult {
+			Self::ensure_not_halted()?;
+			ensure_root(origin)?;
+			ensure!(Self::import_open(), MyError::<T>::ImportNotOpen);
+			ensure!(entries.len() <= T::MaxImportChunk::get() as usize, MyError::<T>::BatchTooLarge);
 
+			let mut total: u64 = 0;
+			for (index, (who, amount)) in entries.iter().enumerate() {
+				ensure!(*amount > 0, MyError::<T>::ZeroAmount);
+				ensure!(
+					!<Balances<T>>::contains_key(who) && !entries[..index].iter().any(|(other, _)| other == who),
+					MyError::<T>::DuplicateImport
+				);
+				total = total.checked_add(*amount).ok_or(MyError::<T>::Overflow)?;
+			}
+			let issuance = Self::get_total_issuance().checked_add(total).ok_or(MyError::<T>::Overflow)?;
+			ensure!(issuance <= Self::get_max_supply(), MyError::<T>::CapExceeded);
+
+			for (who, amount) in &entries {
+				Self::issue(who, *amount)?;
+			}
+			ImportedTotal::mutate(|imported| *imported = imported.saturating_add(total));
+			Self::deposit_event(RawEvent::BalancesImported(entries.len() as u32, total));
 			Ok(())
 		}
 
-		//Returns the name of the token
+		//Ends the balance import and unblocks transfers. Root only.
 		#[weight = 10_000]
-		fn name(origin) -> DispatchResult {
-			let _user = ensure_signed(origin)?;
-			let name = Self::get_name();
-			Self::deposit_event(RawEvent::NameReturned(name));
+		fn close_import(origin) -> DispatchResult {
+			Self::ensure_not_halted()?;
+			ensure_root(origin)?;
+			ensure!(Self::import_open(), MyError::<T>::ImportNotOpen);
+
+			ImportOpen::kill();
+			Self::deposit_event(RawEvent::ImportClosed(Self::imported_total()));
 			Ok(())
 		}
 
-		//Returns the symbol of the token. E.g. “HIX”.
+		//Issues 'amount' new tokens to 'beneficiary'. Only the token owner or a Minter may call
+		//this and the total supply can never exceed the cap.
 		#[weight = 10_000]
-		fn symbol(origin) -> DispatchResult {
-			let _user = ensure_signed(origin)?;
-			let ticker = Self::get_ticker();
-			Self::deposit_event(RawEvent::TickerReturned(ticker));
+		fn mint_to(origin, beneficiary: T::AccountId, #[compact] amount: u64) -> DispatchResult {
+			Self::ensure_not_halted()?;
+			Self::ensure_owner_or_role(origin, Role::Minter)?;
+			ensure!(amount > 0, MyError::<T>::ZeroAmount);
+			Self::issue(&beneficiary, amount)
+		}
+
+		//Nominates 'new_owner' to take over the token. Nothing changes until the nominee calls
+		//accept_ownership; a later nomination replaces the pending one.
+		#[weight = 10_000]
+		fn transfer_ownership(origin, new_owner: <T::Lookup as StaticLookup>::Source) -> DispatchResult {
+			Self::ensure_not_halted()?;
+			let owner = Self::ensure_owner(origin)?;
+			let new_owner = T::Lookup::lookup(new_owner)?;
+
+			<PendingOwner<T>>::put(&new_owner);
+			Self::deposit_event(RawEvent::OwnershipTransferStarted(owner, new_owner));
 			Ok(())
 		}
 
-		//Returns the number of decimals the token uses - e.g. 8, means to divide the token amount by 100000000 to get its user representation. Default is 18.
+		//Completes a pending ownership transfer. Only the nominated account may call this.
 		#[weight = 10_000]
-		fn decimals(origin) -> DispatchResult {
-			let _user = ensure_signed(origin)?;
-			let decimals = Self::get_decimals();
-			Self::deposit_event(RawEvent::DecimalsReturned(decimals));
+		fn accept_ownership(origin) -> DispatchResult {
+			Self::ensure_not_halted()?;
+			let user = ensure_signed(origin)?;
+			let pending = Self::get_pending_owner().ok_or(MyError::<T>::NoPendingOwner)?;
+			ensure!(pending == user, MyError::<T>::NotPendingOwner);
+			let old = Self::get_owner().ok_or(MyError::<T>::NoOwner)?;
+
+			<Owner<T>>::put(&user);
+			<PendingOwner<T>>::kill();
+			Self::deposit_event(RawEvent::OwnershipTransferred(old, user));
 			Ok(())
 		}
 
-		//Returns the total token supply.
+		//Withdraws a pending ownership nomination
 		#[weight = 10_000]
-		fn total_supply(origin) -> DispatchResult {
-			let _user = ensure_signed(origin)?;
-			let max_supply = Self::get_max_supply();
-			Self::deposit_event(RawEvent::TotalSupplyReturned(max_supply));
+		fn cancel_ownership_transfer(origin) -> DispatchResult {
+			Self::ensure_not_halted()?;
+			let owner = Self::ensure_owner(origin)?;
+			let pending = <PendingOwner<T>>::take().ok_or(MyError::<T>::NoPendingOwner)?;
+			Self::deposit_event(RawEvent::OwnershipTransferCancelled(owner, pending));
 			Ok(())
 		}
 
-		//Returns the account balance of an account
+		//Stops every transfer and burn until unpause is called. Approvals, queries and admin
+		//calls keep working. Owner or Pauser only.
 		#[weight = 10_000]
-		fn balance_of(origin) -> DispatchResult {
-			let user = ensure_signed(origin)?;
-			ensure!(<Balances<T>>::contains_key(&user), MyError::<T>::NoValueStored);
-			let owner_original_value = <Balances<T>>::get(&user);
+		fn pause(origin) -> DispatchResult {
+			Self::ensure_not_halted()?;
+			let who = Self::ensure_owner_or_role(origin, Role::Pauser)?;
+			ensure!(!Self::get_paused(), MyError::<T>::TokenPaused);
 
-			Self::deposit_event(RawEvent::BalanceReturned(owner_original_value));
+			Paused::put(true);
+			Self::deposit_event(RawEvent::Paused(who));
 			Ok(())
 		}
 
-		//Transfers value amount of tokens from origin to 'to'
+		//Lets transfers and burns through again. Owner, Pauser or root.
 		#[weight = 10_000]
-		fn transfer(origin, to: T::AccountId, value: u64) -> DispatchResult {
-			let user = ensure_signed(origin)?;
-			ensure!(<Balances<T>>::contains_key(&user), MyError::<T>::NoValueStored);
-			let owner_original_value = <Balances<T>>::get(&user);
-			ensure!(owner_original_value >= value, MyError::<T>::NotEnoughFunds);
-			let receiver_original_value = <Balances<T>>::get(&to);
+		fn unpause(origin) -> DispatchResult {
+			Self::ensure_not_halted()?;
+			let who = if ensure_root(origin.clone()).is_ok() {
+				None
+			} else if Self::get_breaker_tripped() {
+				//Only the owner or root may lift a pause caused by the circuit breaker
+				Some(Self::ensure_owner(origin)?)
+			} else {
+				Some(Self::ensure_owner_or_role(origin, Role::Pauser)?)
+			};
+			ensure!(Self::get_paused(), MyError::<T>::NotPaused);
+
+			Paused::kill();
+			BreakerTripped::kill();
+			Self::deposit_event(RawEvent::Unpaused(who));
+			Ok(())
+		}
 
-			let owner_resulting_value = owner_original_value - value;
-			let receiver_resulting_value = receiver_original_value + value;
-			
-			<Balances<T>>::insert(&user, owner_resulting_value);
-			<Balances<T>>::insert(&to, receiver_resulting_value);
+		//Sets the most value that may move through transfers in a single block before the
+		//circuit breaker pauses the token. None switches the breaker off. Owner only.
+		#[weight = 10_000]
+		fn set_max_volume_per_block(origin, max: Option<u64>) -> DispatchResult {
+			Self::ensure_not_halted()?;
+			Self::ensure_owner(origin)?;
+			ensure!(max != Some(0), MyError::<T>::ZeroAmount);
 
-			Self::deposit_event(RawEvent::Transfer(user, to, value));
+			MaxVolumePerBlock::set(max);
+			Self::deposit_event(RawEvent::MaxVolumePerBlockSet(max));
 			Ok(())
 		}
 
-		// Transfers value amount of tokens from address 'from' to address 'to' depending on the allowance
+		//Stops 'who' from sending or burning tokens, including through allowances it granted.
+		//With 'block_incoming' it cannot receive either. Owner or Freezer only; freezing an
+		//already frozen account replaces its 'block_incoming' setting.
 		#[weight = 10_000]
-		fn transfer_from(origin, from: T::AccountId, to: T::AccountId, value: u64) -> DispatchResult {
-			let _user = ensure_signed(origin)?;
+		fn freeze_account(origin, who: <T::Lookup as StaticLookup>::Source, block_incoming: bool) -> DispatchResult {
+			Self::ensure_not_halted()?;
+			Self::ensure_owner_or_role(origin, Role::Freezer)?;
+			let who = T::Lookup::lookup(who)?;
 
-			let allowance = <Allowances<T>>::get(&from, &to);
-			ensure!(allowance >= value, MyError::<T>::NotEnoughAllowance);
-			let updated_allowance = allowance - value ;
-			ensure!(<Balances<T>>::contains_key(&from), MyError::<T>::NoValueStored);
-			let owner_original_value = <Balances<T>>::get(&from);
-			ensure!(owner_original_value >= value, MyError::<T>::NotEnoughFunds);
-			let receiver_original_value = <Balances<T>>::get(&to);
-		
-			let owner_resulting_value = owner_original_value - value;
-			let receiver_resulting_value = receiver_original_value + value;
+			<FrozenAccounts<T>>::insert(&who, block_incoming);
+			Self::deposit_event(RawEvent::AccountFrozen(who, block_incoming));
+			Ok(())
+		}
 
-			<Allowances<T>>::insert(&from, &to, updated_allowance);				
-			<Balances<T>>::insert(&from, owner_resulting_value);
-			<Balances<T>>::insert(&to, receiver_resulting_value);
+		//Lifts a freeze_account
+		#[weight = 10_000]
+		fn thaw_account(origin, who: <T::Lookup as StaticLookup>::Source) -> DispatchResult {
+			Self::ensure_not_halted()?;
+			Self::ensure_owner_or_role(origin, Role::Freezer)?;
+			let who = T::Lookup::lookup(who)?;
+			ensure!(Self::is_frozen(&who), MyError::<T>::NotFrozen);
 
-			Self::deposit_event(RawEvent::Transfer(from, to, value));
+			<FrozenAccounts<T>>::remove(&who);
+			Self::deposit_event(RawEvent::AccountThawed(who));
 			Ok(())
 		}
 
-		//Allows spender 'to' to withdraw from your account multiple times, up to the value amount.
-		//If this function is called again it overwrites the current allowance with value.
+		//Makes holdings non-transferable (or transferable again). While soulbound, transfers
+		//only go through when the sender, or the spender of an allowance, is exempt. Minting
+		//and burning are unaffected. Owner only.
 		#[weight = 10_000]
-		fn approve(origin, to: T::AccountId, value: u64) -> DispatchResult {
-			let user = ensure_signed(origin)?;
+		fn set_soulbound(origin, enabled: bool) -> DispatchResult {
+			Self::ensure_not_halted()?;
+			Self::ensure_owner(origin)?;
+
+			Soulbound::put(enabled);
+			Self::deposit_event(RawEvent::SoulboundSet(enabled));
+			Ok(())
+		}
+
+		//Adds or removes 'who' from the accounts that may still transfer while soulbound
+		#[weight = 10_000]
+		fn set_soulbound_exempt(origin, who: <T::Lookup as StaticLookup>::Source, exempt: bool) -> DispatchResult {
+			Self::ensure_not_halted()?;
+			Self::ensure_owner(origin)?;
+			let who = T::Lookup::lookup(who)?;
+
+			if exempt {
+				<SoulboundExempt<T>>::insert(&who, true);
+			} else {
+				<SoulboundExempt<T>>::remove(&who);
+			}
+			Self::deposit_event(RawEvent::SoulboundExemptSet(who, exempt));
+			Ok(())
+		}
+
+		//Switches between open transfers and whitelist-only transfers. The whitelist is kept
+		//when going back to Open. Owner only.
+		#[weight = 10_000]
+		fn set_transfer_policy(origin, policy: TransferPolicy) -> DispatchResult {
+			Self::ensure_not_halted()?;
+			Self::ensure_owner(origin)?;
+
+			TransferPolicyOf::put(policy);
+			Self::deposit_event(RawEvent::TransferPolicySet(policy));
+			Ok(())
+		}
+
+		//Lets 'who' send and receive under the WhitelistOnly policy
+		#[weight = 10_000]
+		fn whitelist_add(origin, who: <T::Lookup as StaticLookup>::Source) -> DispatchResult {
+			Self::ensure_not_halted()?;
+			Self::ensure_owner(origin)?;
+			let who = T::Lookup::lookup(who)?;
+
+			<Whitelisted<T>>::insert(&who, true);
+			Self::deposit_event(RawEvent::WhitelistAdded(who));
+			Ok(())
+		}
+
+		//Takes 'who' off the whitelist
+		#[weight = 10_000]
+		fn whitelist_remove(origin, who: <T::Lookup as StaticLookup>::Source) -> DispatchResult {
+			Self::ensure_not_halted()?;
+			Self::ensure_owner(origin)?;
+			let who = T::Lookup::lookup(who)?;
+			ensure!(Self::whitelisted(&who), MyError::<T>::NotWhitelisted);
+
+			<Whitelisted<T>>::remove(&who);
+			Self::deposit_event(RawEvent::WhitelistRemoved(who));
+			Ok(())
+		}
+
+		//Blocks 'who' from sending, receiving and being approved as a spender. Owner only.
+		#[weight = 10_000]
+		fn add_to_blacklist(origin, who: <T::Lookup as StaticLookup>::Source) -> DispatchResult {
+			Self::ensure_not_halted()?;
+			Self::ensure_owner(origin)?;
+			let who = T::Lookup::lookup(who)?;
+			ensure!(!Self::is_blacklisted(&who), MyError::<T>::AlreadyBlacklisted);
+
+			<Blacklist<T>>::insert(&who, true);
+			Self::deposit_event(RawEvent::Blacklisted(who));
+			Ok(())
+		}
+
+		//Takes 'who' off the blacklist
+		#[weight = 10_000]
+		fn remove_from_blacklist(origin, who: <T::Lookup as StaticLookup>::Source) -> DispatchResult {
+			Self::ensure_not_halted()?;
+			Self::ensure_owner(origin)?;
+			let who = T::Lookup::lookup(who)?;
+			ensure!(Self::is_blacklisted(&who), MyError::<T>::NotBlacklisted);
+
+			<Blacklist<T>>::remove(&who);
+			Self::deposit_event(RawEvent::Unblacklisted(who));
+			Ok(())
+		}
+
+		//Grants 'role' to 'who'. Owner only; the owner implicitly holds every role.
+		#[weight = 10_000]
+		fn grant_role(origin, who: <T::Lookup as StaticLookup>::Source, role: Role) -> DispatchResult {
+			Self::ensure_not_halted()?;
+			Self::ensure_owner(origin)?;
+			let who = T::Lookup::lookup(who)?;
+
+			<Roles<T>>::mutate(&who, |roles| *roles |= role.bit());
+			Self::deposit_event(RawEvent::RoleGranted(who, role));
+			Ok(())
+		}
+
+		//Takes 'role' away from 'who' with immediate effect
+		#[weight = 10_000]
+		fn revoke_role(origin, who: <T::Lookup as StaticLookup>::Source, role: Role) -> DispatchResult {
+			Self::ensure_not_halted()?;
+			Self::ensure_owner(origin)?;
+			let who = T::Lookup::lookup(who)?;
+			ensure!(Self::has_role(&who, role), MyError::<T>::RoleNotHeld);
 
-			<Allowances<T>>::insert(&user, &to, value);
+			<Roles<T>>::mutate_exists(&who, |roles| {
+				let remaining = roles.unwrap_or_default() & !role.bit();
+				*roles = if remaining == 0 { None } else { Some(remaining) };
+			});
+			Self::deposit_event(RawEvent::RoleRevoked(who, role));
+			Ok(())
+		}
+
+		//Replaces the token metadata, validated the same way as in mint. Once any transfer has
+		//happened (TransferCounter is non-zero) decimals can only be changed with 'force', as
+		//it rescales every balance for wallets and exchanges.
+		#[weight = 10_000]
+		fn set_metadata(origin, name: Vec<u8>, ticker: Vec<u8>, decimals: u8, force: bool) -> DispatchResult {
+			Self::ensure_not_halted()?;
+			Self::ensure_owner(origin)?;
+			ensure!(!Self::get_metadata_frozen(), MyError::<T>::MetadataIsFrozen);
+			let ticker = Self::validate_metadata(&name, ticker, decimals)?;
+			let old_decimals = Self::get_decimals();
+			ensure!(decimals == old_decimals || force || TransferCounter::get() == 0, MyError::<T>::DecimalsLocked);
+
+			let old_name = Name::mutate(|stored| sp_std::mem::replace(stored, name.clone()));
+			let old_ticker = Ticker::mutate(|stored| sp_std::mem::replace(stored, ticker.clone()));
+			Decimals::put(decimals);
+			Self::deposit_event(RawEvent::MetadataUpdated(old_name, old_ticker, old_decimals, name, ticker, decimals));
+			Ok(())
+		}
+
+		//Locks name, ticker and decimals for good. There is no way to undo this.
+		#[weight = 10_000]
+		fn freeze_metadata(origin) -> DispatchResult {
+			Self::ensure_not_halted()?;
+			Self::ensure_owner(origin)?;
+			ensure!(!Self::get_metadata_frozen(), MyError::<T>::MetadataIsFrozen);
 
-			Self::deposit_event(RawEvent::Approval(user, to, value));
+			MetadataFrozen::put(true);
+			Self::deposit_event(RawEvent::MetadataFrozen);
 			Ok(())
 		}
 
-		//Returns the amount which spender 'to' is still allowed to withdraw from owner.
+		//Caps what origin can send within any window of 'period_blocks' blocks at
+		//'max_per_period'; zero removes the limit. Tightening applies at once, while loosening or
+		//removing only takes effect TransferLimitDelay blocks later, so a stolen key cannot
+		//lift the cap straight away.
 		#[weight = 10_000]
-		fn allowance(origin, to: T::AccountId) -> DispatchResult {
+		fn set_transfer_limit(origin, #[compact] max_per_period: u64, period_blocks: T::BlockNumber) -> DispatchResult {
+			Self::ensure_not_halted()?;
 			let user = ensure_signed(origin)?;
-			ensure!(<Allowances<T>>::contains_key(&user, &to), MyError::<T>::NoValueStored);
+			ensure!(max_per_period == 0 || period_blocks > 0u32.into(), MyError::<T>::ZeroAmount);
+
+			let now = frame_system::Module::<T>::block_number();
+			Self::apply_pending_limit(&user, now);
+			match Self::transfer_limits(&user) {
+				Some(current) if max_per_period == 0 || max_per_period > current.max_per_period || period_blocks < current.period_blocks => {
+					let effective_at = now.saturating_add(T::TransferLimitDelay::get());
+					<PendingTransferLimit<T>>::insert(&user, (max_per_period, period_blocks, effective_at));
+					Self::deposit_event(RawEvent::TransferLimitScheduled(user, max_per_period, period_blocks, effective_at));
+				}
+				current => {
+					ensure!(max_per_period > 0, MyError::<T>::ZeroAmount);
+					let limit = match current {
+						Some(current) => RateLimit { max_per_period, period_blocks, ..current },
+						None => RateLimit::new(max_per_period, period_blocks, now),
+					};
+					<TransferLimits<T>>::insert(&user, limit);
+					<PendingTransferLimit<T>>::remove(&user);
+					Self::deposit_event(RawEvent::TransferLimitSet(user, max_per_period, period_blocks));
+				}
+			}
+			Ok(())
+		}
 
-			let value = <Allowances<T>>::get(&user, &to);
+		//Sets the fee taken from every transfer for the treasury, in basis points of the
+		//value sent; recipients receive the rest. None, or a rate of zero, removes the fee.
+		//Root's force_transfer is not charged. Owner only.
+		#[weight = 10_000]
+		fn set_transfer_fee(origin, fee: Option<TransferFee>) -> DispatchResult {
+			Self::ensure_not_halted()?;
+			Self::ensure_owner(origin)?;
+			let fee = fee.filter(|fee| fee.rate_bps > 0);
+			if let Some(fee) = fee.as_ref() {
+				ensure!(fee.rate_bps <= 10_000, MyError::<T>::FeeTooHigh);
+			}
 
-			Self::deposit_event(RawEvent::AllowanceReturned(value));
+			TransferFeeConfig::set(fee.clone());
+			Self::deposit_event(RawEvent::TransferFeeSet(fee));
 			Ok(())
 		}
+
+		//Destroys 'rate_bps' basis points of every transfer, on top of any transfer fee; the
+		//recipient receives the rest. Zero switches the burn off. Owner only.
+		#[weight = 10_000]
+		fn set_burn_rate(origin, rate_bps: u16) -> DispatchResult {
+			Self::ensure_not_halted()?;
+			Self::ensure_owner(origin)?;
+			ensure!(rate_bps <= 10_000, MyError::<T>::FeeTooHigh);
+
+			BurnRateBps::put(rate_bps);
+			Self::deposit_event(RawEvent::BurnRateSet(rate_bps));
+			Ok(())
+		}
+
+		//Exempts 'who' from the transfer fee and burn, whether it sends or receives. Owner only.
+		#[weight = 10_000]
+		fn exempt_add(origin, who: <T::Lookup as StaticLookup>::Source) -> DispatchResult {
+			Self::ensure_not_halted()?;
+			Self::ensure_owner(origin)?;
+			let who = T::Lookup::lookup(who)?;
+
+			<DeductionExempt<T>>::insert(&who, true);
+			Self::deposit_event(RawEvent::ExemptAdded(who));
+			Ok(())
+		}
+
+		//Subjects 'who' to the transfer fee and burn again
+		#[weight = 10_000]
+		fn exempt_remove(origin, who: <T::Lookup as StaticLookup>::Source) -> DispatchResult {
+			Self::ensure_not_halted()?;
+			Self::ensure_owner(origin)?;
+			let who = T::Lookup::lookup(who)?;
+			ensure!(Self::deduction_exempt(&who), MyError::<T>::NotExempt);
+
+			<DeductionExempt<T>>::remove(&who);
+			Self::deposit_event(RawEvent::ExemptRemoved(who));
+			Ok(())
+		}
+
+		//Caps the amount a single transfer, or a single batch item, may move. None lifts the
+		//cap. Root's force_transfer is not affected. Owner only.
+		#[weight = 10_000]
+		fn set_max_transfer(origin, max: Option<u64>) -> DispatchResult {
+			Self::ensure_not_halted()?;
+			Self::ensure_owner(origin)?;
+			ensure!(max != Some(0), MyError::<T>::ZeroAmount);
+
+			MaxTransferAmount::set(max);
+			Self::deposit_event(RawEvent::MaxTransferSet(max));
+			Ok(())
+		}
+
+		//Refuses transfers below 'min', except those emptying the sender. Zero disables the
+		//check. Owner only.
+		#[weight = 10_000]
+		fn set_min_transfer(origin, #[compact] min: u64) -> DispatchResult {
+			Self::ensure_not_halted()?;
+			Self::ensure_owner(origin)?;
+
+			MinTransferAmount::put(min);
+			Self::deposit_event(RawEvent::MinTransferSet(min));
+			Ok(())
+		}
+
+		//Sets the token URI and logo hash wallets display. An empty 'uri' or a None 'logo_hash'
+		//clears the respective field.
+		#[weight = 10_000]
+		fn set_token_uri(origin, uri: Vec<u8>, logo_hash: Option<[u8; 32]>) -> DispatchResult {
+			Self::ensure_not_halted()?;
+			Self::ensure_owner(origin)?;
+			ensure!(!Self::get_metadata_frozen(), MyError::<T>::MetadataIsFrozen);
+			ensure!(uri.len() <= T::MaxUriLength::get() as usize, MyError::<T>::UriTooLong);
+
+			if uri.is_empty() {
+				TokenUri::kill();
+			} else {
+				TokenUri::put(&uri);
+			}
+			LogoHash::set(logo_hash);
+			Self::deposit_event(RawEvent::TokenUriUpdated(uri, logo_hash));
+			Ok(())
+		}
+
+		//Gives up ownership for good, leaving every owner-gated call unusable. 'confirm' must
+		//repeat the token ticker so this cannot be sent by accident.
+		#[weight = 10_000]
+		fn renounce_ownership(origin, confirm: Vec<u8>) -> DispatchResult {
+			Self::ensure_not_halted()?;
+			let owner = Self::ensure_owner(origin)?;
+			ensure!(confirm.to_ascii_uppercase() == Self::get_ticker(), MyError::<T>::RenounceNotConfirmed);
+
+			<Owner<T>>::kill();
+			<PendingOwner<T>>::kill();
+			Self::deposit_event(RawEvent::OwnershipRenounced(owner));
+			Ok(())
+		}
+
+		//Returns the name of the token
+		#[weight = 10_000]
+		fn name(origin) -> DispatchResult {
+			Self::ensure_not_halted()?;
+			let user = ensure_signed(origin)?;
+			let name = Self::get_name();
+			Self::deposit_event(RawEvent::NameReturned(user, name));
+			Ok(())
+		}
+
+		//Returns the symbol of the token. E.g. “HIX”.
+		#[weight = 10_000]
+		fn symbol(origin) -> DispatchResult {
+			Self::ensure_not_halted()?;
+			let user = ensure_signed(origin)?;
+			let ticker = Self::get_ticker();
+			Self::deposit_event(RawEvent::TickerReturned(user, ticker));
+			Ok(())
+		}
+
+		//Returns the number of decimals the token uses - e.g. 8, means to divide the token amount by 100000000 to get its user representation. Default is 18.
+		#[weight = 10_000]
+		fn decimals(origin) -> DispatchResult {
+			Self::ensure_not_halted()?;
+			let user = ensure_signed(origin)?;
+			let decimals = Self::get_decimals();
+			Self::deposit_event(RawEvent::DecimalsReturned(user, decimals));
+			Ok(())
+		}
+
+		//Returns name, ticker, decimals, URI, logo hash and whether the metadata is frozen in
+		//one event
+		#[weight = 10_000]
+		fn token_info(origin) -> DispatchResult {
+			Self::ensure_not_halted()?;
+			let user = ensure_signed(origin)?;
+			Self::deposit_event(RawEvent::TokenInfoReturned(
+				user,
+				Self::get_name(),
+				Self::get_ticker(),
+				Self::get_decimals(),
+				Self::get_token_uri(),
+				Self::get_logo_hash(),
+				Self::get_metadata_frozen(),
+			));
+			Ok(())
+		}
+
+		//Returns the total token supply currently in existence.
+		#[weight = 10_000]
+		fn total_supply(origin) -> DispatchResult {
+			Self::ensure_not_halted()?;
+			let user = ensure_signed(origin)?;
+			let total_issuance = Self::get_total_issuance();
+			Self::deposit_event(RawEvent::TotalSupplyReturned(user, total_issuance));
+			Ok(())
+		}
+
+		//Returns the cap that the total supply can never exceed.
+		#[weight = 10_000]
+		fn max_supply(origin) -> DispatchResult {
+			Self::ensure_not_halted()?;
+			let user = ensure_signed(origin)?;
+			let max_supply = Self::get_max_supply();
+			Self::deposit_event(RawEvent::MaxSupplyReturned(user, max_supply));
+			Ok(())
+		}
+
+		//Returns the free (transferable) balance of 'who'; pass your own account to query
+		//yourself. Accounts without an entry report 0.
+		#[weight = 10_000]
+		fn balance_of(origin, who: T::AccountId) -> DispatchResult {
+			Self::ensure_not_halted()?;
+			let _user = ensure_signed(origin)?;
+			let value = Self::free_balance(&who);
+
+			Self::deposit_event(RawEvent::BalanceReturned(who, value));
+			Ok(())
+		}
+
+		//Transfers value amount of tokens from origin to 'to'
+		#[weight = 10_000]
+		fn transfer(origin, to: <T::Lookup as StaticLookup>::Source, #[compact] value: u64) -> DispatchResult {
+			Self::ensure_not_halted()?;
+			let user = ensure_signed(origin)?;
+			let to = T::Lookup::lookup(to)?;
+			Self::do_transfer(&user, &to, value, false)
+		}
+
+		//Transfers like transfer and attaches 'memo' (an order id, deposit reference...) to the
+		//emitted TransferWithMemo event. The memo is not stored. This is the only way to pay an
+		//account that set require_memo.
+		#[weight = 10_000]
+		fn transfer_with_memo(origin, to: <T::Lookup as StaticLookup>::Source, #[compact] value: u64, memo: Vec<u8>) -> DispatchResult {
+			Self::ensure_not_halted()?;
+			let user = ensure_signed(origin)?;
+			let to = T::Lookup::lookup(to)?;
+			ensure!(memo.len() <= T::MaxMemoLength::get() as usize, MyError::<T>::MemoTooLong);
+
+			Self::move_balance(&user, &user, &to, value, false)?;
+
+			Self::deposit_event(RawEvent::TransferWithMemo(user, to, value, memo));
+			Ok(())
+		}
+
+		//Flags (or unflags) origin as requiring a memo on incoming transfers. While set, only
+		//transfer_with_memo can credit the account.
+		#[weight = 10_000]
+		fn require_memo(origin, enabled: bool) -> DispatchResult {
+			Self::ensure_not_halted()?;
+			let user = ensure_signed(origin)?;
+			if enabled {
+				<MemoRequired<T>>::insert(&user, true);
+			} else {
+				<MemoRequired<T>>::remove(&user);
+			}
+			Self::deposit_event(RawEvent::MemoRequirementSet(user, enabled));
+			Ok(())
+		}
+
+		//Transfers like transfer, tagged with a client-chosen 'reference'. Resubmitting the same
+		//reference from the same sender within ReferenceRetention blocks fails with
+		//DuplicateReference instead of moving the funds a second time.
+		#[weight = 10_000]
+		fn transfer_idempotent(origin, to: <T::Lookup as StaticLookup>::Source, #[compact] value: u64, reference: [u8; 32]) -> DispatchResult {
+			Self::ensure_not_halted()?;
+			let user = ensure_signed(origin)?;
+			let to = T::Lookup::lookup(to)?;
+
+			let now = frame_system::Module::<T>::block_number();
+			if let Some(used_at) = Self::used_references(&user, &reference) {
+				ensure!(now >= used_at.saturating_add(T::ReferenceRetention::get()), MyError::<T>::DuplicateReference);
+			}
+			let expires_at = now.saturating_add(T::ReferenceRetention::get());
+			let mut expiring = Self::reference_expiries(expires_at);
+			ensure!(expiring.len() < T::MaxReferencesPerBlock::get() as usize, MyError::<T>::TooManyReferences);
+
+			Self::do_transfer(&user, &to, value, false)?;
+
+			expiring.push((user.clone(), reference));
+			<ReferenceExpiries<T>>::insert(expires_at, expiring);
+			<UsedReferences<T>>::insert(&user, &reference, now);
+			Ok(())
+		}
+
+		//Like transfer, but fails with WouldKill instead of leaving the caller with less than
+		//the minimum balance, including with nothing at all.
+		#[weight = 10_000]
+		fn transfer_keep_alive(origin, to: <T::Lookup as StaticLookup>::Source, #[compact] value: u64) -> DispatchResult {
+			Self::ensure_not_halted()?;
+			let user = ensure_signed(origin)?;
+			let to = T::Lookup::lookup(to)?;
+			Self::do_transfer(&user, &to, value, true)
+		}
+
+		//Sends tokens from the caller's balance to every listed recipient in one go. A repeated
+		//recipient is merged into its first entry, and nothing moves unless every transfer
+		//goes through.
+		#[weight = 10_000 * (recipients.len().min(T::MaxAirdropSize::get() as usize) as u64 + 1)]
+		fn airdrop(origin, recipients: Vec<(T::AccountId, u64)>) -> DispatchResult {
+			Self::ensure_not_halted()?;
+			let user = ensure_signed(origin)?;
+			ensure!(recipients.len() <= T::MaxAirdropSize::get() as usize, MyError::<T>::AirdropTooLarge);
+			ensure!(!Self::get_paused(), MyError::<T>::TokenPaused);
+
+			let mut merged: Vec<(T::AccountId, u64)> = Vec::with_capacity(recipients.len());
+			let mut total: u64 = 0;
+			for (to, value) in recipients {
+				total = total.checked_add(value).ok_or(MyError::<T>::Overflow)?;
+				match merged.iter_mut().find(|(dest, _)| *dest == to) {
+					Some((_, merged_value)) => *merged_value += value,
+					None => merged.push((to, value)),
+				}
+			}
+			ensure!(total <= Self::free_balance(&user), MyError::<T>::NotEnoughFunds);
+			//Checked up front, as a breaker tripped inside the airdrop would be rolled back with it
+			Self::ensure_volume_available(total)?;
+
+			Self::do_batch_transfer(&user, &merged)?;
+			Self::deposit_event(RawEvent::AirdropCompleted(merged.len() as u32, total));
+			Ok(())
+		}
+
+		//Transfers to several recipients at once; a recipient may appear more than once and
+		//gets a Transfer event per item. In Atomic mode the caller is debited a single time for
+		//the total and nothing moves if any item fails. In BestEffort mode failing items are
+		//skipped with a BatchItemFailed event and only the items that went through are charged.
+		#[weight = 10_000 * (recipients.len().min(T::MaxBatchSize::get() as usize) as u64 + 1)]
+		fn batch_transfer(origin, recipients: Vec<(T::AccountId, u64)>, mode: BatchMode) -> DispatchResultWithPostInfo {
+			Self::ensure_not_halted()?;
+			let user = ensure_signed(origin)?;
+			ensure!(recipients.len() <= T::MaxBatchSize::get() as usize, MyError::<T>::BatchTooLarge);
+			ensure!(!Self::get_paused(), MyError::<T>::TokenPaused);
+			//Checked for the whole batch up front, as a breaker tripped inside the batch would be
+			//rolled back with it
+			let volume = recipients.iter().fold(0u64, |total, (_, value)| total.saturating_add(*value));
+			Self::ensure_volume_available(volume)?;
+
+			let succeeded = match mode {
+				BatchMode::Atomic => {
+					Self::do_batch_transfer(&user, &recipients)?;
+					recipients.len() as u32
+				}
+				BatchMode::BestEffort => {
+					ensure!(!recipients.is_empty(), MyError::<T>::EmptyBatch);
+					let mut succeeded: u32 = 0;
+					for (index, (to, value)) in recipients.iter().enumerate() {
+						match Self::try_batch_item(|| Self::do_transfer(&user, to, *value, false)) {
+							Ok(()) => succeeded += 1,
+							Err(error) => Self::deposit_event(RawEvent::BatchItemFailed(index as u32, error)),
+						}
+					}
+					succeeded
+				}
+			};
+
+			let failed = recipients.len() as u32 - succeeded;
+			Self::deposit_event(RawEvent::BatchCompleted(succeeded, failed));
+			Ok(Some(10_000 * (succeeded as u64 + 1)).into())
+		}
+
+		//Transfers 'total' to 'beneficiary' like transfer, but what arrives is locked and vests
+		//'per_block' tokens per block from 'start_block' on. Call vest to release what has vested.
+		//At least MinVestedTransfer must arrive, so dust cannot use up the schedule slots.
+		#[weight = 10_000]
+		#[transactional]
+		fn vested_transfer(
+			origin,
+			beneficiary: <T::Lookup as StaticLookup>::Source,
+			#[compact] total: u64,
+			start_block: T::BlockNumber,
+			#[compact] per_block: u64,
+		) -> DispatchResult {
+			Self::ensure_not_halted()?;
+			let user = ensure_signed(origin)?;
+			let beneficiary = T::Lookup::lookup(beneficiary)?;
+			ensure!(per_block > 0, MyError::<T>::ZeroAmount);
+			let mut schedules = Self::vesting(&beneficiary);
+			ensure!(schedules.len() < T::MaxVestingSchedules::get() as usize, MyError::<T>::TooManyVestingSchedules);
+
+			let received = Self::transfer_received(&user, &beneficiary, total)?;
+			ensure!(received >= T::MinVestedTransfer::get(), MyError::<T>::AmountLow);
+
+			let schedule = VestingSchedule { locked: received, per_block, starting_block: start_block };
+			schedules.push(schedule.clone());
+			<Vesting<T>>::insert(&beneficiary, schedules);
+			Self::update_vesting_lock(&beneficiary)?;
+			Self::deposit_event(RawEvent::VestingCreated(beneficiary, schedule));
+			Ok(())
+		}
+
+		//Releases whatever has vested in the caller's schedules and drops the finished ones
+		#[weight = 10_000]
+		fn vest(origin) -> DispatchResult {
+			Self::ensure_not_halted()?;
+			let user = ensure_signed(origin)?;
+			ensure!(<Vesting<T>>::contains_key(&user), MyError::<T>::NotVesting);
+			Self::update_vesting_lock(&user)
+		}
+
+		//Transfers value to 'dest' like transfer, but what arrives cannot be moved or burned
+		//before block 'unlock_at'. At least MinLockedTransfer must arrive, so dust cannot use up
+		//the lock slots of 'dest'.
+		#[weight = 10_000]
+		#[transactional]
+		fn transfer_locked(
+			origin,
+			dest: <T::Lookup as StaticLookup>::Source,
+			#[compact] value: u64,
+			unlock_at: T::BlockNumber,
+		) -> DispatchResult {
+			Self::ensure_not_halted()?;
+			let user = ensure_signed(origin)?;
+			let dest = T::Lookup::lookup(dest)?;
+			ensure!(unlock_at > frame_system::Module::<T>::block_number(), MyError::<T>::InvalidUnlockBlock);
+
+			let received = Self::transfer_received(&user, &dest, value)?;
+			ensure!(received >= T::MinLockedTransfer::get(), MyError::<T>::AmountLow);
+
+			<TimeLocks<T>>::append(&dest, (received, unlock_at));
+			Self::rebuild_time_locks(&dest)?;
+			Self::deposit_event(RawEvent::LockedTransfer(user, dest, value, unlock_at));
+			Ok(())
+		}
+
+		//Reserves value now and transfers it to 'dest' at the start of block 'at_block'. The id
+		//to cancel with is announced in TransferScheduled.
+		#[weight = 10_000]
+		fn schedule_transfer(
+			origin,
+			dest: <T::Lookup as StaticLookup>::Source,
+			#[compact] value: u64,
+			at_block: T::BlockNumber,
+		) -> DispatchResult {
+			Self::ensure_not_halted()?;
+			let user = ensure_signed(origin)?;
+			let dest = T::Lookup::lookup(dest)?;
+			ensure!(at_block > frame_system::Module::<T>::block_number(), MyError::<T>::InvalidUnlockBlock);
+			ensure!(user != dest, MyError::<T>::SelfTransfer);
+			ensure!(
+				(Self::schedule_queue(at_block).len() as u32) < T::MaxScheduledPerBlock::get(),
+				MyError::<T>::TooManyScheduled
+			);
+			let id = Self::next_schedule_id();
+			let next_id = id.checked_add(1).ok_or(MyError::<T>::Overflow)?;
+
+			Self::reserve(&user, value)?;
+			NextScheduleId::put(next_id);
+			<ScheduleQueue<T>>::append(at_block, id);
+			<Scheduled<T>>::insert(id, ScheduledTransfer { from: user.clone(), dest: dest.clone(), value, at: at_block });
+			Self::deposit_event(RawEvent::TransferScheduled(id, user, dest, value, at_block));
+			Ok(())
+		}
+
+		//Drops a scheduled transfer of the caller and releases its reservation
+		#[weight = 10_000]
+		fn cancel_scheduled(origin, id: u64) -> DispatchResult {
+			Self::ensure_not_halted()?;
+			let user = ensure_signed(origin)?;
+			let scheduled = Self::scheduled(id).ok_or(MyError::<T>::UnknownScheduled)?;
+			ensure!(scheduled.from == user, MyError::<T>::NotScheduler);
+
+			<ScheduleQueue<T>>::mutate_exists(scheduled.at, |queue| {
+				if let Some(ids) = queue {
+					ids.retain(|queued| *queued != id);
+					if ids.is_empty() {
+						*queue = None;
+					}
+				}
+			});
+			<Scheduled<T>>::remove(id);
+			Self::unreserve(&user, scheduled.value);
+			Self::deposit_event(RawEvent::ScheduledCancelled(id));
+			Ok(())
+		}
+
+		//Lets 'merchant' collect 'amount_per_period' from the caller once every 'period_blocks'
+		//blocks, starting now, for at most 'max_periods' periods. The id is announced in
+		//SubscriptionCreated.
+		#[weight = 10_000]
+		fn create_subscription(
+			origin,
+			merchant: <T::Lookup as StaticLookup>::Source,
+			#[compact] amount_per_period: u64,
+			period_blocks: T::BlockNumber,
+			max_periods: Option<u32>,
+		) -> DispatchResult {
+			Self::ensure_not_halted()?;
+			let user = ensure_signed(origin)?;
+			let merchant = T::Lookup::lookup(merchant)?;
+			ensure!(amount_per_period > 0 && period_blocks > 0u32.into() && max_periods != Some(0), MyError::<T>::ZeroAmount);
+			ensure!(user != merchant, MyError::<T>::SelfTransfer);
+			let id = Self::next_subscription_id();
+			let next_id = id.checked_add(1).ok_or(MyError::<T>::Overflow)?;
+
+			let subscription = Subscription {
+				merchant,
+				amount_per_period,
+				period_blocks,
+				max_periods,
+				collected: 0,
+				next_due: frame_system::Module::<T>::block_number(),
+			};
+			NextSubscriptionId::put(next_id);
+			<Subscriptions<T>>::insert(&user, id, subscription.clone());
+			Self::deposit_event(RawEvent::SubscriptionCreated(user, id, subscription));
+			Ok(())
+		}
+
+		//Collects the current period of a subscription. Only its merchant can call this, at
+		//most once per period. Periods that were not collected in time are forfeited rather
+		//than accumulated.
+		#[weight = 10_000]
+		fn collect(origin, subscriber: <T::Lookup as StaticLookup>::Source, subscription_id: u64) -> DispatchResult {
+			Self::ensure_not_halted()?;
+			let user = ensure_signed(origin)?;
+			let subscriber = T::Lookup::lookup(subscriber)?;
+			let mut subscription = Self::subscriptions(&subscriber, subscription_id).ok_or(MyError::<T>::UnknownSubscription)?;
+			ensure!(subscription.merchant == user, MyError::<T>::NotMerchant);
+			let now = frame_system::Module::<T>::block_number();
+			ensure!(now >= subscription.next_due, MyError::<T>::PeriodNotElapsed);
+
+			let amount = subscription.amount_per_period;
+			Self::do_transfer_by(&user, &subscriber, &user, amount, false)?;
+
+			subscription.collected = subscription.collected.saturating_add(1);
+			subscription.next_due = now.saturating_add(subscription.period_blocks);
+			Self::deposit_event(RawEvent::SubscriptionCollected(subscriber.clone(), subscription_id, amount));
+			if subscription.max_periods.map_or(false, |max| subscription.collected >= max) {
+				<Subscriptions<T>>::remove(&subscriber, subscription_id);
+				Self::deposit_event(RawEvent::SubscriptionEnded(subscriber, subscription_id));
+			} else {
+				<Subscriptions<T>>::insert(&subscriber, subscription_id, subscription);
+			}
+			Ok(())
+		}
+
+		//Ends a subscription. Either the subscriber or the merchant may call this.
+		#[weight = 10_000]
+		fn cancel_subscription(origin, subscriber: <T::Lookup as StaticLookup>::Source, subscription_id: u64) -> DispatchResult {
+			Self::ensure_not_halted()?;
+			let user = ensure_signed(origin)?;
+			let subscriber = T::Lookup::lookup(subscriber)?;
+			let subscription = Self::subscriptions(&subscriber, subscription_id).ok_or(MyError::<T>::UnknownSubscription)?;
+			ensure!(user == subscriber || user == subscription.merchant, MyError::<T>::NotSubscriptionParty);
+
+			<Subscriptions<T>>::remove(&subscriber, subscription_id);
+			Self::deposit_event(RawEvent::SubscriptionCancelled(subscriber, subscription_id, user));
+			Ok(())
+		}
+
+		//Streams 'total' to 'recipient' evenly between 'start_block' and 'end_block'. The tokens
+		//are held by the pallet account and the stream id is announced in StreamCreated.
+		#[weight = 10_000]
+		fn create_stream(
+			origin,
+			recipient: <T::Lookup as StaticLookup>::Source,
+			#[compact] total: u64,
+			start_block: T::BlockNumber,
+			end_block: T::BlockNumber,
+		) -> DispatchResult {
+			Self::ensure_not_halted()?;
+			let user = ensure_signed(origin)?;
+			let recipient = T::Lookup::lookup(recipient)?;
+			ensure!(end_block > start_block, MyError::<T>::InvalidStreamPeriod);
+			ensure!(user != recipient, MyError::<T>::SelfTransfer);
+			let id = Self::next_stream_id();
+			let next_id = id.checked_add(1).ok_or(MyError::<T>::Overflow)?;
+
+			let total = Self::transfer_received(&user, &Self::account_id(), total)?;
+
+			Earmarked::mutate(|earmarked| *earmarked = earmarked.saturating_add(total));
+			NextStreamId::put(next_id);
+			let stream = Stream { sender: user, recipient, total, withdrawn: 0, start_block, end_block };
+			<Streams<T>>::insert(id, stream.clone());
+			Self::deposit_event(RawEvent::StreamCreated(id, stream));
+			Ok(())
+		}
+
+		//Pays the recipient of stream 'id' whatever has streamed and not yet been withdrawn.
+		//The stream is removed once fully paid.
+		#[weight = 10_000]
+		fn withdraw_from_stream(origin, stream_id: u64) -> DispatchResult {
+			Self::ensure_not_halted()?;
+			let user = ensure_signed(origin)?;
+			let mut stream = Self::streams(stream_id).ok_or(MyError::<T>::UnknownStream)?;
+			ensure!(stream.recipient == user, MyError::<T>::NotStreamRecipient);
+
+			let now = frame_system::Module::<T>::block_number();
+			let amount = stream.accrued_at(now) - stream.withdrawn;
+			ensure!(amount > 0, MyError::<T>::NothingToWithdraw);
+
+			Self::pay_from_pallet(&user, amount)?;
+			stream.withdrawn += amount;
+			Self::deposit_event(RawEvent::StreamWithdrawn(stream_id, user, amount));
+			if stream.withdrawn == stream.total {
+				<Streams<T>>::remove(stream_id);
+			} else {
+				<Streams<T>>::insert(stream_id, stream);
+			}
+			Ok(())
+		}
+
+		//Stops stream 'id': the recipient gets what has streamed so far and the sender gets the
+		//rest back. Either party may call this.
+		#[weight = 10_000]
+		#[transactional]
+		fn cancel_stream(origin, stream_id: u64) -> DispatchResult {
+			Self::ensure_not_halted()?;
+			let user = ensure_signed(origin)?;
+			let stream = Self::streams(stream_id).ok_or(MyError::<T>::UnknownStream)?;
+			ensure!(user == stream.sender || user == stream.recipient, MyError::<T>::NotStreamParty);
+
+			let accrued = stream.accrued_at(frame_system::Module::<T>::block_number());
+			let to_recipient = accrued - stream.withdrawn;
+			let to_sender = stream.total - accrued;
+			Self::pay_from_pallet(&stream.recipient, to_recipient)?;
+			Self::pay_from_pallet(&stream.sender, to_sender)?;
+
+			<Streams<T>>::remove(stream_id);
+			Self::deposit_event(RawEvent::StreamCancelled(stream_id, to_recipient, to_sender));
+			Ok(())
+		}
+
+		//Requests 'amount' from 'payer', or from anyone with None, payable until 'expires_at'.
+		//The invoice id is announced in InvoiceCreated.
+		#[weight = 10_000]
+		fn create_invoice(
+			origin,
+			payer: Option<T::AccountId>,
+			#[compact] amount: u64,
+			memo: Vec<u8>,
+			expires_at: T::BlockNumber,
+		) -> DispatchResult {
+			Self::ensure_not_halted()?;
+			let user = ensure_signed(origin)?;
+			ensure!(amount > 0, MyError::<T>::ZeroAmount);
+			ensure!(memo.len() <= T::MaxMemoLength::get() as usize, MyError::<T>::MemoTooLong);
+			ensure!(expires_at >= frame_system::Module::<T>::block_number(), MyError::<T>::InvoiceExpired);
+			let id = Self::next_invoice_id();
+			let next_id = id.checked_add(1).ok_or(MyError::<T>::Overflow)?;
+
+			NextInvoiceId::put(next_id);
+			let invoice = Invoice { creator: user, payer, amount, memo, expires_at };
+			<Invoices<T>>::insert(id, invoice.clone());
+			Self::deposit_event(RawEvent::InvoiceCreated(id, invoice));
+			Ok(())
+		}
+
+		//Pays invoice 'id' to its creator from the caller and removes it. The invoice itself
+		//identifies the payment, so creators that require a memo accept it.
+		#[weight = 10_000]
+		fn pay_invoice(origin, id: u64) -> DispatchResult {
+			Self::ensure_not_halted()?;
+			let user = ensure_signed(origin)?;
+			let invoice = Self::invoices(id).ok_or(MyError::<T>::UnknownInvoice)?;
+			ensure!(invoice.payer.as_ref().map_or(true, |payer| payer == &user), MyError::<T>::NotInvoicePayer);
+			ensure!(frame_system::Module::<T>::block_number() <= invoice.expires_at, MyError::<T>::InvoiceExpired);
+
+			Self::move_balance(&user, &user, &invoice.creator, invoice.amount, false)?;
+			<Invoices<T>>::remove(id);
+			Self::deposit_event(RawEvent::InvoicePaid(id, user, invoice.creator, invoice.amount));
+			Ok(())
+		}
+
+		//Withdraws an unpaid invoice. Only its creator can call this.
+		#[weight = 10_000]
+		fn cancel_invoice(origin, id: u64) -> DispatchResult {
+			Self::ensure_not_halted()?;
+			let user = ensure_signed(origin)?;
+			let invoice = Self::invoices(id).ok_or(MyError::<T>::UnknownInvoice)?;
+			ensure!(invoice.creator == user, MyError::<T>::NotInvoiceCreator);
+
+			<Invoices<T>>::remove(id);
+			Self::deposit_event(RawEvent::InvoiceCancelled(id));
+			Ok(())
+		}
+
+		//Sets the tokens newly issued every block, within the cap, as rewards for stakers. Owner only.
+		#[weight = 10_000]
+		fn set_staking_reward(origin, #[compact] per_block: u64) -> DispatchResult {
+			Self::ensure_not_halted()?;
+			Self::ensure_owner(origin)?;
+
+			Self::update_reward_pool();
+			StakingRewardPerBlock::put(per_block);
+			Self::deposit_event(RawEvent::StakingRewardSet(per_block));
+			Ok(())
+		}
+
+		//Stakes 'amount' of the caller's tokens in the pallet account. Pending rewards are paid
+		//out first.
+		#[weight = 10_000]
+		#[transactional]
+		fn stake(origin, #[compact] amount: u64) -> DispatchResult {
+			Self::ensure_not_halted()?;
+			let user = ensure_signed(origin)?;
+			ensure!(amount > 0, MyError::<T>::ZeroAmount);
+
+			Self::update_reward_pool();
+			Self::pay_rewards(&user)?;
+			let received = Self::transfer_received(&user, &Self::account_id(), amount)?;
+
+			Earmarked::mutate(|earmarked| *earmarked = earmarked.saturating_add(received));
+			TotalStaked::mutate(|total| *total = total.saturating_add(received));
+			<Stakes<T>>::mutate(&user, |stake| {
+				stake.amount = stake.amount.saturating_add(received);
+				stake.reward_debt = stake.amount as u128 * Self::reward_per_share() / REWARD_PRECISION;
+			});
+			Self::deposit_event(RawEvent::Staked(user, received));
+			Ok(())
+		}
+
+		//Unstakes 'amount'. With an UnbondingPeriod the tokens can be taken out with
+		//withdraw_unbonded once it has passed; otherwise they are paid out at once. Pending
+		//rewards are paid out first.
+		#[weight = 10_000]
+		#[transactional]
+		fn unstake(origin, #[compact] amount: u64) -> DispatchResult {
+			Self::ensure_not_halted()?;
+			let user = ensure_signed(origin)?;
+			ensure!(amount > 0, MyError::<T>::ZeroAmount);
+			let mut stake = Self::stakes(&user);
+			ensure!(stake.amount >= amount, MyError::<T>::NotEnoughStaked);
+
+			Self::update_reward_pool();
+			Self::pay_rewards(&user)?;
+			stake.amount -= amount;
+			stake.reward_debt = stake.amount as u128 * Self::reward_per_share() / REWARD_PRECISION;
+			if stake.amount == 0 {
+				<Stakes<T>>::remove(&user);
+			} else {
+				<Stakes<T>>::insert(&user, stake);
+			}
+			TotalStaked::mutate(|total| *total = total.saturating_sub(amount));
+
+			let period = T::UnbondingPeriod::get();
+			if period == 0u32.into() {
+				Self::pay_from_pallet(&user, amount)?;
+			} else {
+				let unlock_at = frame_system::Module::<T>::block_number().saturating_add(period);
+				<Unbonding<T>>::mutate(&user, |unbonding| {
+					let (pending, _) = unbonding.take().unwrap_or_default();
+					*unbonding = Some((pending.saturating_add(amount), unlock_at));
+				});
+			}
+			Self::deposit_event(RawEvent::Unstaked(user, amount));
+			Ok(())
+		}
+
+		//Pays out the caller's unstaked tokens once their unbonding period is over. Unstaking
+		//again restarts the period for everything still unbonding.
+		#[weight = 10_000]
+		fn withdraw_unbonded(origin) -> DispatchResult {
+			Self::ensure_not_halted()?;
+			let user = ensure_signed(origin)?;
+			let (amount, unlock_at) = Self::unbonding(&user).ok_or(MyError::<T>::NothingToWithdraw)?;
+			ensure!(frame_system::Module::<T>::block_number() >= unlock_at, MyError::<T>::StillUnbonding);
+
+			Self::pay_from_pallet(&user, amount)?;
+			<Unbonding<T>>::remove(&user);
+			Self::deposit_event(RawEvent::Withdrawn(user, amount));
+			Ok(())
+		}
+
+		//Pays out the staking rewards the caller has earned so far
+		#[weight = 10_000]
+		#[transactional]
+		fn claim_rewards(origin) -> DispatchResult {
+			Self::ensure_not_halted()?;
+			let user = ensure_signed(origin)?;
+			ensure!(<Stakes<T>>::contains_key(&user), MyError::<T>::NotEnoughStaked);
+
+			Self::update_reward_pool();
+			let paid = Self::pay_rewards(&user)?;
+			ensure!(paid > 0, MyError::<T>::NothingToWithdraw);
+			Ok(())
+		}
+
+		//Opens a crowdsale selling tokens at 'rate' per unit of native currency between
+		//'start_block' and 'end_block'. Owner only, and only once the previous sale is settled.
+		#[weight = 10_000]
+		fn start_sale(
+			origin,
+			#[compact] rate: u64,
+			soft_cap: BalanceOf<T>,
+			hard_cap: BalanceOf<T>,
+			start_block: T::BlockNumber,
+			end_block: T::BlockNumber,
+		) -> DispatchResult {
+			Self::ensure_not_halted()?;
+			let owner = Self::ensure_owner(origin)?;
+			ensure!(rate > 0 && soft_cap <= hard_cap && !hard_cap.is_zero(), MyError::<T>::InvalidSale);
+			ensure!(start_block <= end_block && end_block >= frame_system::Module::<T>::block_number(), MyError::<T>::InvalidSale);
+			//A failed sale stays until every contribution has been refunded
+			if let Some(sale) = Self::current_sale() {
+				ensure!(sale.finalized && (sale.raised >= sale.soft_cap || sale.raised.is_zero()), MyError::<T>::SaleActive);
+			}
+
+			//Contributions to earlier sales are left under their own index instead of cleared here
+			let index = Self::sale_index().checked_add(1).ok_or(MyError::<T>::Overflow)?;
+			SaleIndex::put(index);
+			let sale = Sale { rate, soft_cap, hard_cap, start_block, end_block, raised: Zero::zero(), finalized: false };
+			<CurrentSale<T>>::put(&sale);
+			Self::deposit_event(RawEvent::SaleStarted(owner, sale));
+			Ok(())
+		}
+
+		//Buys tokens for 'native_amount' of native currency, which is held by the sale account
+		//until the sale is finalized. The tokens are newly issued under the cap.
+		#[weight = 10_000]
+		#[transactional]
+		fn buy(origin, native_amount: BalanceOf<T>) -> DispatchResult {
+			Self::ensure_not_halted()?;
+			let user = ensure_signed(origin)?;
+			ensure!(!native_amount.is_zero(), MyError::<T>::ZeroAmount);
+			let mut sale = Self::current_sale().ok_or(MyError::<T>::NoSale)?;
+			ensure!(sale.is_open(&frame_system::Module::<T>::block_number()), MyError::<T>::SaleNotOpen);
+			let raised = sale.raised.checked_add(&native_amount).ok_or(MyError::<T>::Overflow)?;
+			ensure!(raised <= sale.hard_cap, MyError::<T>::HardCapExceeded);
+			let native: u64 = native_amount.try_into().map_err(|_| MyError::<T>::Overflow)?;
+			let tokens = native.checked_mul(sale.rate).ok_or(MyError::<T>::Overflow)?;
+
+			T::Currency::transfer(&user, &Self::sale_account(), native_amount, ExistenceRequirement::KeepAlive)?;
+			Self::issue(&user, tokens)?;
+			sale.raised = raised;
+			<CurrentSale<T>>::put(sale);
+			<Contributions<T>>::mutate(Self::sale_index(), &user, |(paid, bought)| {
+				*paid = paid.saturating_add(native_amount);
+				*bought = bought.saturating_add(tokens);
+			});
+			Self::deposit_event(RawEvent::TokensPurchased(user, native_amount, tokens));
+			Ok(())
+		}
+
+		//Closes the sale once it has ended. When the soft cap was met the raised currency goes
+		//to the owner; otherwise contributors can take theirs back with claim_refund.
+		#[weight = 10_000]
+		fn finalize_sale(origin) -> DispatchResult {
+			Self::ensure_not_halted()?;
+			let owner = Self::ensure_owner(origin)?;
+			let mut sale = Self::current_sale().ok_or(MyError::<T>::NoSale)?;
+			ensure!(!sale.finalized, MyError::<T>::SaleFinalized);
+			ensure!(sale.has_ended(&frame_system::Module::<T>::block_number()), MyError::<T>::SaleNotEnded);
+
+			let succeeded = sale.raised >= sale.soft_cap;
+			if succeeded {
+				T::Currency::transfer(&Self::sale_account(), &owner, sale.raised, ExistenceRequirement::AllowDeath)?;
+			}
+			sale.finalized = true;
+			<CurrentSale<T>>::put(&sale);
+			Self::deposit_event(RawEvent::SaleFinalized(sale.raised, succeeded));
+			Ok(())
+		}
+
+		//Returns the caller's contribution to a sale that ended below its soft cap, burning
+		//the tokens it bought. A caller who no longer holds all of them is refunded in
+		//proportion to what can be burned and can claim the rest after getting them back.
+		#[weight = 10_000]
+		#[transactional]
+		fn claim_refund(origin) -> DispatchResult {
+			Self::ensure_not_halted()?;
+			let user = ensure_signed(origin)?;
+			let mut sale = Self::current_sale().ok_or(MyError::<T>::NoSale)?;
+			ensure!(sale.has_ended(&frame_system::Module::<T>::block_number()), MyError::<T>::SaleNotEnded);
+			ensure!(sale.raised < sale.soft_cap, MyError::<T>::SoftCapReached);
+			let (paid, bought) = <Contributions<T>>::get(Self::sale_index(), &user);
+			ensure!(!paid.is_zero(), MyError::<T>::NoContribution);
+			let burned = Self::free_balance(&user).saturating_sub(Self::locked_balance(&user)).min(bought);
+			ensure!(burned > 0, MyError::<T>::NotEnoughFunds);
+			let refund: BalanceOf<T> = if burned == bought {
+				paid
+			} else {
+				(paid.saturated_into::<u128>() * burned as u128 / bought as u128).saturated_into()
+			};
+
+			Self::do_burn(&user, burned)?;
+			T::Currency::transfer(&Self::sale_account(), &user, refund, ExistenceRequirement::AllowDeath)?;
+			if burned == bought {
+				<Contributions<T>>::remove(Self::sale_index(), &user);
+			} else {
+				<Contributions<T>>::insert(Self::sale_index(), &user, (paid.saturating_sub(refund), bought - burned));
+			}
+			sale.raised = sale.raised.saturating_sub(refund);
+			<CurrentSale<T>>::put(sale);
+			Self::deposit_event(RawEvent::Refunded(user, refund, burned));
+			Ok(())
+		}
+
+		//Moves 'amount' of the caller's native currency into the wrap account and issues the
+		//same amount of tokens to the caller, within the cap
+		#[weight = 10_000]
+		#[transactional]
+		fn wrap(origin, amount: BalanceOf<T>) -> DispatchResult {
+			Self::ensure_not_halted()?;
+			let user = ensure_signed(origin)?;
+			ensure!(!amount.is_zero(), MyError::<T>::ZeroAmount);
+			let tokens: u64 = amount.saturated_into();
+			ensure!(amount == tokens.saturated_into(), MyError::<T>::Overflow);
+			let wrapped = Self::wrapped_supply().checked_add(tokens).ok_or(MyError::<T>::Overflow)?;
+
+			T::Currency::transfer(&user, &Self::wrap_account(), amount, ExistenceRequirement::KeepAlive)?;
+			Self::issue(&user, tokens)?;
+			WrappedSupply::put(wrapped);
+			Self::deposit_event(RawEvent::Wrapped(user, tokens));
+			Ok(())
+		}
+
+		//Burns 'amount' of the caller's tokens and releases the same amount of native currency
+		//from the wrap account to the caller
+		#[weight = 10_000]
+		#[transactional]
+		fn unwrap(origin, #[compact] amount: u64) -> DispatchResult {
+			Self::ensure_not_halted()?;
+			let user = ensure_signed(origin)?;
+			let wrapped = Self::wrapped_supply().checked_sub(amount).ok_or(MyError::<T>::InsufficientBacking)?;
+
+			Self::do_burn(&user, amount)?;
+			T::Currency::transfer(&Self::wrap_account(), &user, amount.saturated_into(), ExistenceRequirement::AllowDeath)?;
+			WrappedSupply::put(wrapped);
+			Self::deposit_event(RawEvent::Unwrapped(user, amount));
+			Ok(())
+		}
+
+		//Sets the native collateral, in basis points of the token amount, required by positions
+		//opened from now on; existing positions keep their ratio. It must be at least 10_000.
+		//Owner only.
+		#[weight = 10_000]
+		fn set_collateral_ratio(origin, ratio_bps: u32) -> DispatchResult {
+			Self::ensure_not_halted()?;
+			Self::ensure_owner(origin)?;
+			ensure!(ratio_bps >= 10_000, MyError::<T>::InvalidCollateralRatio);
+
+			CollateralRatioBps::put(ratio_bps);
+			Self::deposit_event(RawEvent::CollateralRatioSet(ratio_bps));
+			Ok(())
+		}
+
+		//Mints 'token_amount' to the caller against native collateral reserved from its
+		//account, opening a position or adding to the one it has
+		#[weight = 10_000]
+		#[transactional]
+		fn mint_collateralized(origin, #[compact] token_amount: u64) -> DispatchResult {
+			Self::ensure_not_halted()?;
+			let user = ensure_signed(origin)?;
+			ensure!(token_amount > 0, MyError::<T>::ZeroAmount);
+			let existing = Self::collateral_positions(&user);
+			let mut position = match existing.clone() {
+				Some(position) => position,
+				None => {
+					let ratio_bps = Self::collateral_ratio_bps();
+					ensure!(ratio_bps > 0, MyError::<T>::InvalidCollateralRatio);
+					CollateralPosition { collateral: Zero::zero(), minted: 0, ratio_bps }
+				}
+			};
+			let collateral = position.collateral_for(token_amount);
+			position.minted = position.minted.checked_add(token_amount).ok_or(MyError::<T>::Overflow)?;
+			position.collateral = position.collateral.checked_add(&collateral).ok_or(MyError::<T>::Overflow)?;
+
+			T::Currency::reserve(&user, collateral)?;
+			Self::issue(&user, token_amount)?;
+			<CollateralPositions<T>>::insert(&user, &position);
+			if existing.is_some() {
+				Self::deposit_event(RawEvent::PositionAdjusted(user, position.collateral, position.minted));
+			} else {
+				Self::deposit_event(RawEvent::PositionOpened(user, position.collateral, position.minted));
+			}
+			Ok(())
+		}
+
+		//Burns 'token_amount' of the caller's tokens and unreserves the matching share of its
+		//collateral. Redeeming everything minted closes the position.
+		#[weight = 10_000]
+		#[transactional]
+		fn redeem(origin, #[compact] token_amount: u64) -> DispatchResult {
+			Self::ensure_not_halted()?;
+			let user = ensure_signed(origin)?;
+			ensure!(token_amount > 0, MyError::<T>::ZeroAmount);
+			let mut position = Self::collateral_positions(&user).ok_or(MyError::<T>::NoPosition)?;
+			ensure!(token_amount <= position.minted, MyError::<T>::ExceedsPosition);
+			let released = position.release_for(token_amount);
+
+			Self::do_burn(&user, token_amount)?;
+			//Reserved collateral can only have shrunk through slashing by another pallet
+			let _ = T::Currency::unreserve(&user, released);
+			position.minted -= token_amount;
+			position.collateral = position.collateral.saturating_sub(released);
+			if position.minted == 0 {
+				<CollateralPositions<T>>::remove(&user);
+				Self::deposit_event(RawEvent::PositionClosed(user, released));
+			} else {
+				<CollateralPositions<T>>::insert(&user, &position);
+				Self::deposit_event(RawEvent::PositionAdjusted(user, position.collateral, position.minted));
+			}
+			Ok(())
+		}
+
+		//Offers 'token_amount' of the caller's tokens to 'counterparty' for 'native_amount' of
+		//native currency until block 'expires_at'. The tokens are reserved meanwhile, and the
+		//swap id is announced in SwapCreated.
+		#[weight = 10_000]
+		fn create_swap(
+			origin,
+			counterparty: <T::Lookup as StaticLookup>::Source,
+			#[compact] token_amount: u64,
+			native_amount: BalanceOf<T>,
+			expires_at: T::BlockNumber,
+		) -> DispatchResult {
+			Self::ensure_not_halted()?;
+			let user = ensure_signed(origin)?;
+			let counterparty = T::Lookup::lookup(counterparty)?;
+			ensure!(user != counterparty, MyError::<T>::SelfTransfer);
+			ensure!(expires_at >= frame_system::Module::<T>::block_number(), MyError::<T>::SwapExpired);
+			let id = Self::next_swap_id();
+			let next_id = id.checked_add(1).ok_or(MyError::<T>::Overflow)?;
+
+			Self::reserve(&user, token_amount)?;
+			NextSwapId::put(next_id);
+			let swap = Swap { creator: user, counterparty, token_amount, native_amount, expires_at };
+			<Swaps<T>>::insert(id, &swap);
+			Self::deposit_event(RawEvent::SwapCreated(id, swap));
+			Ok(())
+		}
+
+		//Pays the native price of swap 'id' to its creator and takes its tokens in return.
+		//Only the counterparty may accept, by the expiry block, and both legs go through or
+		//neither does.
+		#[weight = 10_000]
+		#[transactional]
+		fn accept_swap(origin, swap_id: u64) -> DispatchResult {
+			Self::ensure_not_halted()?;
+			let user = ensure_signed(origin)?;
+			let swap = Self::swaps(swap_id).ok_or(MyError::<T>::UnknownSwap)?;
+			ensure!(swap.counterparty == user, MyError::<T>::NotSwapCounterparty);
+			ensure!(frame_system::Module::<T>::block_number() <= swap.expires_at, MyError::<T>::SwapExpired);
+
+			T::Currency::transfer(&user, &swap.creator, swap.native_amount, ExistenceRequirement::KeepAlive)?;
+			ensure!(Self::unreserve(&swap.creator, swap.token_amount) == swap.token_amount, MyError::<T>::ReservationSlashed);
+			Self::do_transfer(&swap.creator, &user, swap.token_amount, false)?;
+			<Swaps<T>>::remove(swap_id);
+			Self::deposit_event(RawEvent::SwapExecuted(swap_id, swap.creator, user, swap.token_amount, swap.native_amount));
+			Ok(())
+		}
+
+		//Withdraws swap 'id' and unreserves its tokens. The creator may cancel at any time,
+		//anyone else once the swap has expired.
+		#[weight = 10_000]
+		fn cancel_swap(origin, swap_id: u64) -> DispatchResult {
+			Self::ensure_not_halted()?;
+			let user = ensure_signed(origin)?;
+			let swap = Self::swaps(swap_id).ok_or(MyError::<T>::UnknownSwap)?;
+			ensure!(
+				swap.creator == user || frame_system::Module::<T>::block_number() > swap.expires_at,
+				MyError::<T>::NotSwapCreator
+			);
+
+			Self::unreserve(&swap.creator, swap.token_amount);
+			<Swaps<T>>::remove(swap_id);
+			Self::deposit_event(RawEvent::SwapCancelled(swap_id));
+			Ok(())
+		}
+
+		//Moves 'amount' from the caller into the dividend pot, shared pro rata among all
+		//holders as of now. Holders take their share out with claim_dividend.
+		#[weight = 10_000]
+		#[transactional]
+		fn fund_distribution(origin, #[compact] amount: u64) -> DispatchResult {
+			Self::ensure_not_halted()?;
+			let user = ensure_signed(origin)?;
+			ensure!(amount > 0, MyError::<T>::ZeroAmount);
+
+			let received = Self::transfer_received(&user, &Self::account_id(), amount)?;
+			let supply = Self::dividend_supply();
+			ensure!(supply > 0, MyError::<T>::NoDividendHolders);
+
+			Earmarked::mutate(|earmarked| *earmarked = earmarked.saturating_add(received));
+			DividendPot::mutate(|pot| *pot = pot.saturating_add(received));
+			MagnifiedDividendPerShare::mutate(|per_share| {
+				*per_share = per_share.saturating_add(received as u128 * DIVIDEND_MAGNITUDE / supply as u128)
+			});
+			Self::deposit_event(RawEvent::DistributionFunded(user, received));
+			Ok(())
+		}
+
+		//Pays out the dividends the caller has accrued and not claimed yet
+		#[weight = 10_000]
+		fn claim_dividend(origin) -> DispatchResult {
+			Self::ensure_not_halted()?;
+			let user = ensure_signed(origin)?;
+			let amount = Self::withdrawable_dividend_of(&user).min(Self::dividend_pot());
+			ensure!(amount > 0, MyError::<T>::NothingToWithdraw);
+
+			Self::pay_from_pallet(&user, amount)?;
+			<WithdrawnDividends<T>>::mutate(&user, |withdrawn| *withdrawn = withdrawn.saturating_add(amount));
+			DividendPot::mutate(|pot| *pot = pot.saturating_sub(amount));
+			Self::deposit_event(RawEvent::DividendClaimed(user, amount));
+			Ok(())
+		}
+
+		//Moves value into escrow for 'beneficiary', who can claim it up to block 'deadline'.
+		//The escrow id is announced in EscrowCreated.
+		#[weight = 10_000]
+		fn create_escrow(
+			origin,
+			beneficiary: <T::Lookup as StaticLookup>::Source,
+			#[compact] value: u64,
+			deadline: T::BlockNumber,
+		) -> DispatchResult {
+			Self::ensure_not_halted()?;
+			let user = ensure_signed(origin)?;
+			let beneficiary = T::Lookup::lookup(beneficiary)?;
+			ensure!(deadline >= frame_system::Module::<T>::block_number(), MyError::<T>::EscrowExpired);
+			let id = Self::next_escrow_id();
+			let next_id = id.checked_add(1).ok_or(MyError::<T>::Overflow)?;
+
+			let value = Self::transfer_received(&user, &Self::account_id(), value)?;
+
+			Earmarked::mutate(|earmarked| *earmarked = earmarked.saturating_add(value));
+			NextEscrowId::put(next_id);
+			<Escrows<T>>::insert(id, Escrow { creator: user.clone(), beneficiary: beneficiary.clone(), value, deadline });
+			Self::deposit_event(RawEvent::EscrowCreated(id, user, beneficiary, value, deadline));
+			Ok(())
+		}
+
+		//Pays escrow 'id' out to its beneficiary, who must call this by the deadline
+		#[weight = 10_000]
+		fn claim_escrow(origin, id: u64) -> DispatchResult {
+			Self::ensure_not_halted()?;
+			let user = ensure_signed(origin)?;
+			let escrow = Self::escrows(id).ok_or(MyError::<T>::UnknownEscrow)?;
+			ensure!(escrow.beneficiary == user, MyError::<T>::NotEscrowBeneficiary);
+			ensure!(frame_system::Module::<T>::block_number() <= escrow.deadline, MyError::<T>::EscrowExpired);
+
+			Self::release_escrow(id, &escrow, &user)?;
+			Self::deposit_event(RawEvent::EscrowClaimed(id, user, escrow.value));
+			Ok(())
+		}
+
+		//Returns escrow 'id' to its creator once the deadline has passed unclaimed
+		#[weight = 10_000]
+		fn refund_escrow(origin, id: u64) -> DispatchResult {
+			Self::ensure_not_halted()?;
+			let user = ensure_signed(origin)?;
+			let escrow = Self::escrows(id).ok_or(MyError::<T>::UnknownEscrow)?;
+			ensure!(escrow.creator == user, MyError::<T>::NotEscrowCreator);
+			ensure!(frame_system::Module::<T>::block_number() > escrow.deadline, MyError::<T>::EscrowNotExpired);
+
+			Self::release_escrow(id, &escrow, &user)?;
+			Self::deposit_event(RawEvent::EscrowRefunded(id, user, escrow.value));
+			Ok(())
+		}
+
+		//Transfers the caller's whole balance, as it stands when the call executes, to 'to'
+		#[weight = 10_000]
+		fn transfer_all(origin, to: <T::Lookup as StaticLookup>::Source) -> DispatchResult {
+			Self::ensure_not_halted()?;
+			let user = ensure_signed(origin)?;
+			let to = T::Lookup::lookup(to)?;
+			let value = Self::free_balance(&user);
+			ensure!(value > 0, MyError::<T>::NoValueStored);
+			Self::do_transfer(&user, &to, value, false)
+		}
+
+		// Transfers value amount of tokens from address 'from' to address 'to' using the allowance 'from' granted to the caller
+		#[weight = 10_000]
+		fn transfer_from(origin, from: T::AccountId, to: <T::Lookup as StaticLookup>::Source, #[compact] value: u64) -> DispatchResult {
+			Self::ensure_not_halted()?;
+			let spender = ensure_signed(origin)?;
+			let to = T::Lookup::lookup(to)?;
+			Self::do_transfer_from(&spender, &from, &to, value)
+		}
+
+		//Sets or, with None, clears the per-block inflation paid to a beneficiary.
+		#[weight = 10_000]
+		fn set_inflation(origin, config: Option<InflationConfigOf<T>>) -> DispatchResult {
+			Self::ensure_not_halted()?;
+			Self::ensure_owner(origin)?;
+			if let Some(config) = config.as_ref() {
+				ensure!(config.per_block > 0, MyError::<T>::ZeroAmount);
+			}
+
+			<Inflation<T>>::set(config.clone());
+
+			Self::deposit_event(RawEvent::InflationSet(config));
+			Ok(())
+		}
+
+		//Lets 'who' issue up to 'quota' tokens through mint_quota until block 'valid_until'.
+		//Replaces any quota 'who' already had.
+		#[weight = 10_000]
+		fn set_minter(origin, who: T::AccountId, #[compact] quota: u64, valid_until: T::BlockNumber) -> DispatchResult {
+			Self::ensure_not_halted()?;
+			Self::ensure_owner(origin)?;
+			ensure!(quota > 0, MyError::<T>::ZeroAmount);
+
+			<Minters<T>>::insert(&who, MinterQuota { remaining: quota, valid_until });
+
+			Self::deposit_event(RawEvent::MinterSet(who, quota, valid_until));
+			Ok(())
+		}
+
+		//Takes away the remaining quota of minter 'who'.
+		#[weight = 10_000]
+		fn revoke_minter(origin, who: T::AccountId) -> DispatchResult {
+			Self::ensure_not_halted()?;
+			Self::ensure_owner(origin)?;
+			ensure!(<Minters<T>>::contains_key(&who), MyError::<T>::NoValueStored);
+
+			<Minters<T>>::remove(&who);
+
+			Self::deposit_event(RawEvent::MinterRevoked(who));
+			Ok(())
+		}
+
+		//Issues 'amount' new tokens to 'beneficiary' out of the caller's minter quota.
+		#[weight = 10_000]
+		fn mint_quota(origin, beneficiary: T::AccountId, #[compact] amount: u64) -> DispatchResult {
+			Self::ensure_not_halted()?;
+			let minter = ensure_signed(origin)?;
+			ensure!(amount > 0, MyError::<T>::ZeroAmount);
+
+			let mut quota = Self::minters(&minter).ok_or(MyError::<T>::NotMinter)?;
+			ensure!(frame_system::Module::<T>::block_number() <= quota.valid_until, MyError::<T>::MinterExpired);
+			quota.remaining = quota.remaining.checked_sub(amount).ok_or(MyError::<T>::QuotaExceeded)?;
+
+			Self::issue(&beneficiary, amount)?;
+			if quota.remaining == 0 {
+				<Minters<T>>::remove(&minter);
+			} else {
+				<Minters<T>>::insert(&minter, &quota);
+			}
+
+			Self::deposit_event(RawEvent::MinterQuotaUsed(minter, amount, quota.remaining));
+			Ok(())
+		}
+
+		//Destroys value amount of the caller's tokens, lowering the total supply. The MaxSupply
+		//cap is left as it is.
+		#[weight = 10_000]
+		fn burn(origin, #[compact] value: u64) -> DispatchResult {
+			Self::ensure_not_halted()?;
+			let user = ensure_signed(origin)?;
+			Self::do_burn(&user, value)
+		}
+
+		//Destroys value amount of the tokens of 'owner', using the allowance 'owner' granted to the caller.
+		#[weight = 10_000]
+		fn burn_from(origin, owner: T::AccountId, #[compact] value: u64) -> DispatchResult {
+			Self::ensure_not_halted()?;
+			let spender = ensure_signed(origin)?;
+			if spender == owner {
+				return Self::do_burn(&owner, value);
+			}
+
+			let remaining = Self::spend_allowance(&owner, &spender, None, value, || Self::do_burn(&owner, value))?;
+
+			Self::deposit_event(RawEvent::BurnedFrom(owner, spender, value, remaining));
+			Ok(())
+		}
+
+		//Spends the allowance 'from' granted to the caller by moving value to the caller itself.
+		//Same as transfer_from with the caller as 'to'.
+		#[weight = 10_000]
+		fn pull(origin, from: T::AccountId, #[compact] value: u64) -> DispatchResult {
+			Self::ensure_not_halted()?;
+			let spender = ensure_signed(origin)?;
+			Self::do_transfer_from(&spender, &from, &spender, value)
+		}
+
+		//Moves, from each listed owner, as much as the caller's allowance and the owner's balance
+		//allow to 'dest'. Owners with nothing to move are skipped with SweepSkipped, owners whose
+		//allowance terms refuse the spend with BatchItemFailed.
+		#[weight = 10_000 * (owners.len().min(T::MaxBatchSize::get() as usize) as u64 + 1)]
+		fn sweep_approved(origin, owners: Vec<T::AccountId>, dest: T::AccountId) -> DispatchResult {
+			Self::ensure_not_halted()?;
+			let spender = ensure_signed(origin)?;
+			ensure!(owners.len() <= T::MaxBatchSize::get() as usize, MyError::<T>::BatchTooLarge);
+			ensure!(!owners.is_empty(), MyError::<T>::EmptyBatch);
+
+			for (index, owner) in owners.into_iter().enumerate() {
+				let balance = Self::free_balance(&owner);
+				let value = if owner == spender {
+					balance
+				} else {
+					balance.min(Self::allowance_amount(&owner, &spender))
+				};
+				if value == 0 {
+					Self::deposit_event(RawEvent::SweepSkipped(owner));
+					continue;
+				}
+				if let Err(error) = Self::try_batch_item(|| Self::do_transfer_from(&spender, &owner, &dest, value)) {
+					Self::deposit_event(RawEvent::BatchItemFailed(index as u32, error));
+				}
+			}
+			Ok(())
+		}
+
+		//Allows 'spender' to withdraw from your account multiple times, up to the value amount.
+		//If this function is called again it overwrites the current allowance with value.
+		#[weight = 10_000]
+		fn approve(origin, spender: <T::Lookup as StaticLookup>::Source, #[compact] value: u64) -> DispatchResult {
+			Self::ensure_not_halted()?;
+			let owner = ensure_signed(origin)?;
+			let spender = T::Lookup::lookup(spender)?;
+			//Approving 0 only makes sense as a revoke of an existing allowance
+			ensure!(value > 0 || <Allowances<T>>::contains_key(&owner, &spender), MyError::<T>::ZeroAmount);
+
+			Self::set_allowance(owner, spender, Allowance::new(value))
+		}
+
+		//Approves several spenders at once, each like approve. A value of 0 removes that
+		//spender's allowance and a spender listed twice ends up with the later value.
+		#[weight = 10_000 * (approvals.len().min(T::MaxBatchSize::get() as usize) as u64 + 1)]
+		#[transactional]
+		fn batch_approve(origin, approvals: Vec<(T::AccountId, u64)>) -> DispatchResult {
+			Self::ensure_not_halted()?;
+			let owner = ensure_signed(origin)?;
+			ensure!(approvals.len() <= T::MaxBatchSize::get() as usize, MyError::<T>::BatchTooLarge);
+			ensure!(!approvals.is_empty(), MyError::<T>::EmptyBatch);
+
+			for (spender, value) in approvals {
+				Self::set_allowance(owner.clone(), spender, Allowance::new(value))?;
+			}
+			Ok(())
+		}
+
+		//Approves 'spender' for value like approve, but the allowance can no longer be spent
+		//once the chain is past block 'expires_at'.
+		#[weight = 10_000]
+		fn approve_with_expiry(
+			origin,
+			spender: <T::Lookup as StaticLookup>::Source,
+			#[compact] value: u64,
+			expires_at: T::BlockNumber,
+		) -> DispatchResult {
+			Self::ensure_not_halted()?;
+			let owner = ensure_signed(origin)?;
+			let spender = T::Lookup::lookup(spender)?;
+			ensure!(value > 0, MyError::<T>::ZeroAmount);
+			ensure!(expires_at >= frame_system::Module::<T>::block_number(), MyError::<T>::AllowanceExpired);
+
+			Self::set_allowance(owner, spender, Allowance { expires_at: Some(expires_at), ..Allowance::new(value) })
+		}
+
+		//Approves 'spender' for value like approve, but the allowance is removed after it has
+		//been used by 'max_uses' transfer_from calls, whatever amount is left.
+		#[weight = 10_000]
+		fn approve_with_uses(
+			origin,
+			spender: <T::Lookup as StaticLookup>::Source,
+			#[compact] value: u64,
+			max_uses: u32,
+		) -> DispatchResult {
+			Self::ensure_not_halted()?;
+			let owner = ensure_signed(origin)?;
+			let spender = T::Lookup::lookup(spender)?;
+			ensure!(value > 0 && max_uses > 0, MyError::<T>::ZeroAmount);
+
+			Self::set_allowance(owner, spender, Allowance { uses_left: Some(max_uses), ..Allowance::new(value) })
+		}
+
+		//Approves 'spender' for value like approve, but funds may only be sent to 'allowed_dest'.
+		#[weight = 10_000]
+		fn approve_restricted(
+			origin,
+			spender: <T::Lookup as StaticLookup>::Source,
+			#[compact] value: u64,
+			allowed_dest: T::AccountId,
+		) -> DispatchResult {
+			Self::ensure_not_halted()?;
+			let owner = ensure_signed(origin)?;
+			let spender = T::Lookup::lookup(spender)?;
+			ensure!(value > 0, MyError::<T>::ZeroAmount);
+
+			Self::set_allowance(owner, spender, Allowance { allowed_dest: Some(allowed_dest), ..Allowance::new(value) })
+		}
+
+		//Approves 'spender' for value like approve, but no more than 'max_per_period' can be
+		//spent within any window of 'period_blocks' blocks.
+		#[weight = 10_000]
+		fn approve_with_rate(
+			origin,
+			spender: <T::Lookup as StaticLookup>::Source,
+			#[compact] value: u64,
+			#[compact] max_per_period: u64,
+			period_blocks: T::BlockNumber,
+		) -> DispatchResult {
+			Self::ensure_not_halted()?;
+			let owner = ensure_signed(origin)?;
+			let spender = T::Lookup::lookup(spender)?;
+			ensure!(value > 0 && max_per_period > 0 && period_blocks > 0u32.into(), MyError::<T>::ZeroAmount);
+
+			let now = frame_system::Module::<T>::block_number();
+			let rate_limit = RateLimit::new(max_per_period, period_blocks, now);
+			Self::set_allowance(owner, spender, Allowance { rate_limit: Some(rate_limit), ..Allowance::new(value) })
+		}
+
+		//Like approve, but only overwrites the allowance if it still equals 'expected_current'.
+		//Protects against a spender front-running an allowance change.
+		#[weight = 10_000]
+		fn approve_checked(
+			origin,
+			spender: <T::Lookup as StaticLookup>::Source,
+			#[compact] expected_current: u64,
+			#[compact] value: u64,
+		) -> DispatchResult {
+			Self::ensure_not_halted()?;
+			let owner = ensure_signed(origin)?;
+			let spender = T::Lookup::lookup(spender)?;
+			ensure!(Self::allowance_amount(&owner, &spender) == expected_current, MyError::<T>::AllowanceMismatch);
+			ensure!(value > 0 || expected_current > 0, MyError::<T>::ZeroAmount);
+
+			Self::set_allowance(owner, spender, Allowance::new(value))
+		}
+
+		//Raises the allowance of 'spender' by delta.
+		#[weight = 10_000]
+		fn increase_allowance(origin, spender: <T::Lookup as StaticLookup>::Source, #[compact] delta: u64) -> DispatchResult {
+			Self::ensure_not_halted()?;
+			let owner = ensure_signed(origin)?;
+			let spender = T::Lookup::lookup(spender)?;
+			ensure!(delta > 0, MyError::<T>::ZeroAmount);
+
+			let mut allowance = Self::allowances(&owner, &spender).unwrap_or_default();
+			allowance.amount = allowance.amount.checked_add(delta).ok_or(MyError::<T>::Overflow)?;
+
+			Self::set_allowance(owner, spender, allowance)
+		}
+
+		//Lowers the allowance of 'spender' by delta. Going below zero fails with NotEnoughAllowance
+		//unless 'clamp' is set, in which case the allowance is simply removed.
+		#[weight = 10_000]
+		fn decrease_allowance(origin, spender: <T::Lookup as StaticLookup>::Source, #[compact] delta: u64, clamp: bool) -> DispatchResult {
+			Self::ensure_not_halted()?;
+			let owner = ensure_signed(origin)?;
+			let spender = T::Lookup::lookup(spender)?;
+			ensure!(delta > 0, MyError::<T>::ZeroAmount);
+
+			let mut allowance = Self::allowances(&owner, &spender).unwrap_or_default();
+			allowance.amount = match allowance.amount.checked_sub(delta) {
+				Some(value) => value,
+				None if clamp => 0,
+				None => return Err(MyError::<T>::NotEnoughAllowance.into()),
+			};
+
+			Self::set_allowance(owner, spender, allowance)
+		}
+
+		//Withdraws the approval given to 'spender' entirely.
+		#[weight = 10_000]
+		fn revoke_allowance(origin, spender: <T::Lookup as StaticLookup>::Source) -> DispatchResult {
+			Self::ensure_not_halted()?;
+			let owner = ensure_signed(origin)?;
+			let spender = T::Lookup::lookup(spender)?;
+			ensure!(<Allowances<T>>::contains_key(&owner, &spender), MyError::<T>::NoValueStored);
+
+			<Allowances<T>>::remove(&owner, &spender);
+
+			Self::deposit_event(RawEvent::ApprovalRevoked(owner, spender));
+			Ok(())
+		}
+
+		//Gives up the allowance 'owner' granted to the caller.
+		#[weight = 10_000]
+		fn renounce_allowance(origin, owner: T::AccountId) -> DispatchResult {
+			Self::ensure_not_halted()?;
+			let spender = ensure_signed(origin)?;
+			ensure!(<Allowances<T>>::contains_key(&owner, &spender), MyError::<T>::NoValueStored);
+
+			<Allowances<T>>::remove(&owner, &spender);
+
+			Self::deposit_event(RawEvent::ApprovalRevoked(owner, spender));
+			Ok(())
+		}
+
+		//Removes up to 'limit' of the allowances the caller has granted. Call repeatedly until
+		//AllowancesCleared reports fewer removals than the limit.
+		#[weight = 10_000 * (*limit as u64 + 1)]
+		fn clear_allowances(origin, limit: u32) -> DispatchResultWithPostInfo {
+			Self::ensure_not_halted()?;
+			let owner = ensure_signed(origin)?;
+
+			let count = Self::clear_allowances_of(&owner, limit);
+
+			Self::deposit_event(RawEvent::AllowancesCleared(owner, count));
+			Ok(Some(10_000 * (count as u64 + 1)).into())
+		}
+
+		//Returns the free and reserved balance of 'who'; together they are its total holding
+		#[weight = 10_000]
+		fn reserved_balance_of(origin, who: T::AccountId) -> DispatchResult {
+			Self::ensure_not_halted()?;
+			let _user = ensure_signed(origin)?;
+			let free = Self::free_balance(&who);
+			let reserved = Self::reserved_balance(&who);
+
+			Self::deposit_event(RawEvent::ReservedBalanceReturned(who, free, reserved));
+			Ok(())
+		}
+
+		//Returns the allowance 'spender' still has from 'owner', with its terms. Callable by anyone.
+		#[weight = 10_000]
+		fn allowance(origin, owner: T::AccountId, spender: T::AccountId) -> DispatchResult {
+			Self::ensure_not_halted()?;
+			let _user = ensure_signed(origin)?;
+			let allowance = Self::allowances(&owner, &spender).unwrap_or_default();
+
+			Self::deposit_event(RawEvent::AllowanceReturned(owner, spender, allowance));
+			Ok(())
+		}
+
+		//Emergency stop for the whole pallet: every call except force_resume fails with Halted,
+		//owner and admin calls included, and inflation stops being paid. Root only.
+		#[weight = 10_000]
+		fn force_halt(origin) -> DispatchResult {
+			Self::ensure_not_halted()?;
+			ensure_root(origin)?;
+
+			Halted::put(true);
+			Self::deposit_event(RawEvent::EmergencyHalted);
+			Ok(())
+		}
+
+		//Lifts a force_halt. This is the one call that still works while halted.
+		#[weight = 10_000]
+		fn force_resume(origin) -> DispatchResult {
+			ensure_root(origin)?;
+			ensure!(Self::get_halted(), MyError::<T>::NotHalted);
+
+			Halted::kill();
+			Self::deposit_event(RawEvent::EmergencyResumed);
+			Ok(())
+		}
+
+		//Reserves 'amount' of the free balance of 'who'. Owner or root.
+		#[weight = 10_000]
+		fn force_reserve(origin, who: <T::Lookup as StaticLookup>::Source, #[compact] amount: u64) -> DispatchResult {
+			Self::ensure_not_halted()?;
+			Self::ensure_owner_or_root(origin)?;
+			let who = T::Lookup::lookup(who)?;
+			Self::reserve(&who, amount)
+		}
+
+		//Returns up to 'amount' of the reserved balance of 'who' to its free balance. Owner or root.
+		#[weight = 10_000]
+		fn force_unreserve(origin, who: <T::Lookup as StaticLookup>::Source, #[compact] amount: u64) -> DispatchResult {
+			Self::ensure_not_halted()?;
+			Self::ensure_owner_or_root(origin)?;
+			let who = T::Lookup::lookup(who)?;
+			Self::unreserve(&who, amount);
+			Ok(())
+		}
+
+		//Destroys up to 'amount' of the reserved balance of 'who'. Owner or root.
+		#[weight = 10_000]
+		fn force_slash_reserved(origin, who: <T::Lookup as StaticLookup>::Source, #[compact] amount: u64) -> DispatchResult {
+			Self::ensure_not_halted()?;
+			Self::ensure_owner_or_root(origin)?;
+			let who = T::Lookup::lookup(who)?;
+			Self::slash_reserved(&who, amount);
+			Ok(())
+		}
+
+		//Moves value from 'from' to 'to' without the holder's signature, ignoring allowances,
+		//the pause, freezes, lists and every other transfer restriction. 'from' still needs
+		//the funds. Root only.
+		#[weight = 10_000]
+		fn force_transfer(
+			origin,
+			from: <T::Lookup as StaticLookup>::Source,
+			to: <T::Lookup as StaticLookup>::Source,
+			#[compact] value: u64,
+		) -> DispatchResult {
+			Self::ensure_not_halted()?;
+			ensure_root(origin)?;
+			let from = T::Lookup::lookup(from)?;
+			let to = T::Lookup::lookup(to)?;
+
+			Self::do_move(&from, &to, value, false)?;
+			Self::deposit_event(RawEvent::ForceTransferred(from, to, value));
+			Ok(())
+		}
+
+		//Destroys 'value' tokens, or with None the whole balance, held by a blacklisted account.
+		//Accounts that are not blacklisted cannot be touched. Root only.
+		#[weight = 10_000]
+		fn confiscate(origin, who: <T::Lookup as StaticLookup>::Source, value: Option<u64>) -> DispatchResult {
+			Self::ensure_not_halted()?;
+			ensure_root(origin)?;
+			let who = T::Lookup::lookup(who)?;
+			ensure!(Self::is_blacklisted(&who), MyError::<T>::NotBlacklisted);
+			let amount = value.unwrap_or_else(|| Self::free_balance(&who));
+			ensure!(amount > 0, MyError::<T>::ZeroAmount);
+			let shares = Self::shares_for_debit(&who, amount);
+
+			Self::try_mutate_balance(&who, |balance| -> DispatchResult {
+				*balance = balance.checked_sub(shares).ok_or(MyError::<T>::NotEnoughFunds)?;
+				Ok(())
+			})?;
+			TotalIssuance::mutate(|issuance| *issuance = issuance.saturating_sub(shares));
+
+			Self::deposit_event(RawEvent::Confiscated(who, amount));
+			Ok(())
+		}
+
+		//Overwrites the balance of 'who' for test networks, moving TotalIssuance by the
+		//difference. The result must stay within MaxSupply unless 'exceed_cap' is set. Zero
+		//removes the account's entry. Root only.
+		#[weight = 10_000]
+		fn force_set_balance(
+			origin,
+			who: <T::Lookup as StaticLookup>::Source,
+			#[compact] new_balance: u64,
+			exceed_cap: bool,
+		) -> DispatchResult {
+			Self::ensure_not_halted()?;
+			ensure_root(origin)?;
+			let who = T::Lookup::lookup(who)?;
+
+			let old_balance = Self::free_balance(&who);
+			let (old_shares, new_shares) = (<Balances<T>>::get(&who), Self::to_shares(new_balance));
+			let total_shares = if new_shares >= old_shares {
+				let total = Self::get_total_shares().checked_add(new_shares - old_shares).ok_or(MyError::<T>::Overflow)?;
+				ensure!(exceed_cap || Self::to_display(total) <= Self::get_max_supply(), MyError::<T>::CapExceeded);
+				total
+			} else {
+				Self::get_total_shares().checked_sub(old_shares - new_shares).ok_or(MyError::<T>::Overflow)?
+			};
+
+			Self::try_mutate_balance(&who, |balance| -> DispatchResult {
+				*balance = new_shares;
+				Ok(())
+			})?;
+			TotalIssuance::put(total_shares);
+
+			Self::deposit_event(RawEvent::BalanceSet(who, old_balance, new_balance));
+			Ok(())
+		}
+
+		//Lets 'to' vote with the caller's balance; delegating to yourself restores the default
+		#[weight = 10_000]
+		fn delegate(origin, to: T::AccountId) -> DispatchResult {
+			Self::ensure_not_halted()?;
+			let user = ensure_signed(origin)?;
+			let old = Self::delegate_of(&user);
+			ensure!(old != to, MyError::<T>::AlreadyDelegated);
+
+			if to == user {
+				<Delegates<T>>::remove(&user);
+			} else {
+				<Delegates<T>>::insert(&user, &to);
+			}
+			let boost = Self::conviction_locks(&user).map_or(0, |lock| lock.boost);
+			Self::move_votes(Some(&old), Some(&to), <Balances<T>>::get(&user).saturating_add(boost));
+			Self::deposit_event(RawEvent::DelegateChanged(user, old, to));
+			Ok(())
+		}
+
+		//Locks 'amount' of the caller's tokens for 'periods' LockPeriods, from 1 up to
+		//MAX_CONVICTION. While locked they vote 1 + 'periods' times. The tokens stay locked
+		//until release_conviction_lock is called after the lock has run out.
+		#[weight = 10_000]
+		fn lock_for_conviction(origin, #[compact] amount: u64, periods: u8) -> DispatchResult {
+			Self::ensure_not_halted()?;
+			let user = ensure_signed(origin)?;
+			ensure!(periods > 0 && periods <= MAX_CONVICTION, MyError::<T>::InvalidConviction);
+			ensure!(!<ConvictionLocks<T>>::contains_key(&user), MyError::<T>::ConvictionLocked);
+			ensure!(amount <= Self::free_balance(&user), MyError::<T>::NotEnoughFunds);
+			let duration = T::LockPeriod::get().saturating_mul((periods as u32).into());
+			let unlock_at = frame_system::Module::<T>::block_number().saturating_add(duration);
+			let boost = Self::to_shares(amount).saturating_mul(periods as u64);
+
+			Self::set_lock(CONVICTION_ID, &user, amount, None)?;
+			Self::move_votes(None, Some(&Self::delegate_of(&user)), boost);
+			let lock = ConvictionLock { amount, periods, unlock_at, boost };
+			<ConvictionLocks<T>>::insert(&user, &lock);
+			Self::deposit_event(RawEvent::ConvictionLocked(user, lock));
+			Ok(())
+		}
+
+		//Lifts the caller's conviction lock and its extra votes once it has run out
+		#[weight = 10_000]
+		fn release_conviction_lock(origin) -> DispatchResult {
+			Self::ensure_not_halted()?;
+			let user = ensure_signed(origin)?;
+			let lock = Self::conviction_locks(&user).ok_or(MyError::<T>::NotConvictionLocked)?;
+			ensure!(frame_system::Module::<T>::block_number() >= lock.unlock_at, MyError::<T>::ConvictionNotExpired);
+
+			Self::remove_lock(CONVICTION_ID, &user);
+			Self::move_votes(Some(&Self::delegate_of(&user)), None, lock.boost);
+			<ConvictionLocks<T>>::remove(&user);
+			Self::deposit_event(RawEvent::ConvictionReleased(user, lock.amount));
+			Ok(())
+		}
+
+		//Records a snapshot of every balance as of now, announced with its id in
+		//SnapshotTaken. Balances are copied lazily as they change, so this is O(1). Owner or root.
+		#[weight = 10_000]
+		fn take_snapshot(origin) -> DispatchResult {
+			Self::ensure_not_halted()?;
+			Self::ensure_owner_or_root(origin)?;
+			let id = Self::current_snapshot().checked_add(1).ok_or(MyError::<T>::Overflow)?;
+			let now = frame_system::Module::<T>::block_number();
+
+			CurrentSnapshot::put(id);
+			<SnapshotBlocks<T>>::insert(id, now);
+			SnapshotFactors::insert(id, Self::scaling_factor());
+			Self::deposit_event(RawEvent::SnapshotTaken(id, now));
+			Ok(())
+		}
+
+		//Makes 'amount' claimable by the holder of Ethereum address 'address', or withdraws its
+		//claim with None. Root only.
+		#[weight = 10_000]
+		fn set_eth_claim(origin, address: EthereumAddress, amount: Option<u64>) -> DispatchResult {
+			Self::ensure_not_halted()?;
+			ensure_root(origin)?;
+
+			match amount {
+				Some(amount) => {
+					ensure!(amount > 0, MyError::<T>::ZeroAmount);
+					EthClaims::insert(address, amount);
+				}
+				None => EthClaims::remove(address),
+			}
+			Self::deposit_event(RawEvent::EthClaimSet(address, amount));
+			Ok(())
+		}
+
+		//Issues to the caller the tokens mapped to the Ethereum address that signed
+		//'eth_signature'. The signature is a personal_sign (EIP-191) over ETH_CLAIM_PREFIX
+		//followed by the lowercase hex of the caller's SCALE-encoded account id, without 0x;
+		//see eth_claim_message. A claim is removed once taken.
+		#[weight = 10_000]
+		fn claim_eth(origin, eth_signature: [u8; 65]) -> DispatchResult {
+			Self::ensure_not_halted()?;
+			let user = ensure_signed(origin)?;
+			let address = Self::eth_recover(&eth_signature, &user).ok_or(MyError::<T>::InvalidEthSignature)?;
+			let amount = Self::eth_claims(address).ok_or(MyError::<T>::NoEthClaim)?;
+
+			Self::issue(&user, amount)?;
+			EthClaims::remove(address);
+			Self::deposit_event(RawEvent::EthClaimed(user, address, amount));
+			Ok(())
+		}
+
+		//Funds a drop of 'total' that the accounts in the merkle tree under 'merkle_root' claim
+		//up to block 'expiry'. The drop id is announced in ClaimDropCreated.
+		#[weight = 10_000]
+		fn create_claim_drop(origin, merkle_root: [u8; 32], #[compact] total: u64, expiry: T::BlockNumber) -> DispatchResult {
+			Self::ensure_not_halted()?;
+			let user = ensure_signed(origin)?;
+			ensure!(expiry >= frame_system::Module::<T>::block_number(), MyError::<T>::DropExpired);
+			let id = Self::next_claim_drop_id();
+			let next_id = id.checked_add(1).ok_or(MyError::<T>::Overflow)?;
+
+			let remaining = Self::transfer_received(&user, &Self::account_id(), total)?;
+
+			Earmarked::mutate(|earmarked| *earmarked = earmarked.saturating_add(remaining));
+			NextClaimDropId::put(next_id);
+			<ClaimDrops<T>>::insert(id, ClaimDrop { creator: user.clone(), merkle_root, remaining, expiry });
+			Self::deposit_event(RawEvent::ClaimDropCreated(id, user, merkle_root, remaining, expiry));
+			Ok(())
+		}
+
+		//Claims 'amount' from drop 'drop_id', proving with 'proof' that the caller's leaf
+		//blake2_256((account, amount)) is in its tree. Each account claims once.
+		#[weight = 10_000 + 1_000 * proof.len() as u64]
+		fn claim(origin, drop_id: u64, #[compact] amount: u64, proof: Vec<[u8; 32]>) -> DispatchResult {
+			Self::ensure_not_halted()?;
+			let user = ensure_signed(origin)?;
+			let mut drop = Self::claim_drops(drop_id).ok_or(MyError::<T>::UnknownDrop)?;
+			ensure!(frame_system::Module::<T>::block_number() <= drop.expiry, MyError::<T>::DropExpired);
+			ensure!(!<Claimed<T>>::contains_key(drop_id, &user), MyError::<T>::AlreadyClaimed);
+			let leaf = sp_io::hashing::blake2_256(&(&user, amount).encode());
+			ensure!(Self::merkle_root_of(leaf, &proof) == drop.merkle_root, MyError::<T>::InvalidProof);
+			drop.remaining = drop.remaining.checked_sub(amount).ok_or(MyError::<T>::NotEnoughFunds)?;
+
+			Self::pay_from_pallet(&user, amount)?;
+			<Claimed<T>>::insert(drop_id, &user, true);
+			<ClaimDrops<T>>::insert(drop_id, drop);
+			Self::deposit_event(RawEvent::DropClaimed(drop_id, user, amount));
+			Ok(())
+		}
+
+		//Returns what is left of drop 'drop_id' to its creator once it has expired and removes
+		//up to 'limit' of its claim records. Once the drop is swept anyone can call again to
+		//remove the rest, until ClaimRecordsCleared reports fewer removals than the limit.
+		#[weight = 10_000 * (*limit as u64 + 1)]
+		fn sweep_claim_drop(origin, drop_id: u64, limit: u32) -> DispatchResultWithPostInfo {
+			Self::ensure_not_halted()?;
+			let user = ensure_signed(origin)?;
+			match Self::claim_drops(drop_id) {
+				Some(drop) => {
+					ensure!(drop.creator == user, MyError::<T>::NotDropCreator);
+					ensure!(frame_system::Module::<T>::block_number() > drop.expiry, MyError::<T>::DropNotExpired);
+
+					Self::pay_from_pallet(&user, drop.remaining)?;
+					<ClaimDrops<T>>::remove(drop_id);
+					Self::deposit_event(RawEvent::ClaimDropSwept(drop_id, drop.remaining));
+				}
+				None => ensure!(<Claimed<T>>::iter_prefix(drop_id).next().is_some(), MyError::<T>::UnknownDrop),
+			}
+
+			let count = <Claimed<T>>::drain_prefix(drop_id).take(limit as usize).count() as u32;
+
+			Self::deposit_event(RawEvent::ClaimRecordsCleared(drop_id, count));
+			Ok(Some(10_000 * (count as u64 + 1)).into())
+		}
+
+		//Sets up the faucet, or switches it off with None. The source pays for the drips
+		//through an allowance to the pallet account, so no one is drained without consent.
+		//Owner only.
+		#[weight = 10_000]
+		fn set_faucet_config(origin, config: Option<FaucetConfigOf<T>>) -> DispatchResult {
+			Self::ensure_not_halted()?;
+			Self::ensure_owner(origin)?;
+
+			match &config {
+				Some(config) => {
+					ensure!(config.drip_amount > 0, MyError::<T>::ZeroAmount);
+					<Faucet<T>>::put(config);
+				}
+				None => <Faucet<T>>::kill(),
+			}
+			Self::deposit_event(RawEvent::FaucetConfigSet(config));
+			Ok(())
+		}
+
+		//Sends the caller one drip from the faucet, at most once per cooldown window
+		#[weight = 10_000]
+		fn faucet(origin) -> DispatchResult {
+			Self::ensure_not_halted()?;
+			let user = ensure_signed(origin)?;
+			let config = Self::faucet_config().ok_or(MyError::<T>::FaucetDisabled)?;
+			let now = frame_system::Module::<T>::block_number();
+			if let Some(last) = Self::last_drip(&user) {
+				ensure!(now >= last.saturating_add(config.cooldown_blocks), MyError::<T>::FaucetCooldown);
+			}
+
+			let faucet = Self::account_id();
+			let amount = config.drip_amount;
+			Self::spend_allowance(&config.source, &faucet, Some(&user), amount, || {
+				Self::do_transfer_by(&faucet, &config.source, &user, amount, false)
+			})?;
+			<LastDrip<T>>::insert(&user, now);
+			Self::deposit_event(RawEvent::Dripped(user, amount));
+			Ok(())
+		}
+
+		//Scales every balance by numerator / denominator at once by changing the number of
+		//tokens each share stands for. Holders keep their share of the supply, and amounts held
+		//outside balances, such as allowances and locks, are left as they are. So are the
+		//tokens of the pallet account, as escrows, streams, stakes and the other funds it holds
+		//are owed in tokens; its shares are reissued at the new factor. Owner only.
+		#[weight = 10_000]
+		#[transactional]
+		fn rebase(origin, numerator: u64, denominator: u64) -> DispatchResult {
+			Self::ensure_not_halted()?;
+			Self::ensure_owner(origin)?;
+			ensure!(numerator > 0 && denominator > 0, MyError::<T>::InvalidRebase);
+			let old_factor = Self::scaling_factor();
+			let new_factor = old_factor.checked_mul(numerator as u128).ok_or(MyError::<T>::Overflow)? / denominator as u128;
+			ensure!(new_factor > 0, MyError::<T>::InvalidRebase);
+
+			let pallet_account = Self::account_id();
+			let held_shares = <Balances<T>>::get(&pallet_account);
+			//Rounded up, so the pallet account never ends up short of what it owes
+			let rebased = (Self::free_balance(&pallet_account) as u128 * SCALING_PRECISION + new_factor - 1) / new_factor;
+			ensure!(rebased <= u64::MAX as u128, MyError::<T>::Overflow);
+			let total_shares = (Self::get_total_shares() - held_shares).checked_add(rebased as u64).ok_or(MyError::<T>::Overflow)?;
+			let supply = (total_shares as u128).checked_mul(new_factor).ok_or(MyError::<T>::Overflow)? / SCALING_PRECISION;
+			ensure!(supply <= u64::MAX as u128, MyError::<T>::Overflow);
+
+			ScalingFactor::put(new_factor);
+			Self::try_mutate_balance(&pallet_account, |balance| -> DispatchResult {
+				*balance = rebased as u64;
+				Ok(())
+			})?;
+			TotalIssuance::put(total_shares);
+			Self::deposit_event(RawEvent::Rebased(old_factor, new_factor, supply as u64));
+			Ok(())
+		}
+
+		//Pre-creates the token account of 'who' against an AccountDeposit reserved from the
+		//caller, so transfers to it are not held to MinBalance.
+		#[weight = 10_000]
+		fn touch(origin, who: <T::Lookup as StaticLookup>::Source) -> DispatchResult {
+			Self::ensure_not_halted()?;
+			let user = ensure_signed(origin)?;
+			let who = T::Lookup::lookup(who)?;
+			ensure!(!<Balances<T>>::contains_key(&who) && !<Touched<T>>::contains_key(&who), MyError::<T>::AlreadyTouched);
+
+			let deposit = T::AccountDeposit::get();
+			T::Currency::reserve(&user, deposit)?;
+			//The placeholder holds a provider like a balance entry does
+			frame_system::Module::<T>::inc_providers(&who);
+			<Touched<T>>::insert(&who, (&user, deposit));
+			Self::deposit_event(RawEvent::Touched(who, user, deposit));
+			Ok(())
+		}
+
+		//Returns the deposit of touched account 'who' to whoever paid it, once 'who' holds no
+		//tokens. Callable by the depositor or 'who' only.
+		#[weight = 10_000]
+		fn refund_touch(origin, who: <T::Lookup as StaticLookup>::Source) -> DispatchResult {
+			Self::ensure_not_halted()?;
+			let user = ensure_signed(origin)?;
+			let who = T::Lookup::lookup(who)?;
+			let (depositor, deposit) = Self::touched(&who).ok_or(MyError::<T>::NotTouched)?;
+			ensure!(user == depositor || user == who, MyError::<T>::NotTouchParty);
+			ensure!(!<Balances<T>>::contains_key(&who) && !<Reserved<T>>::contains_key(&who), MyError::<T>::AccountNotEmpty);
+
+			T::Currency::unreserve(&depositor, deposit);
+			<Touched<T>>::remove(&who);
+			let _ = frame_system::Module::<T>::dec_providers(&who);
+			Self::deposit_event(RawEvent::TouchRefunded(who, depositor, deposit));
+			Ok(())
+		}
+
+		//Lowers the smallest balance an account may be created with. It can never be raised,
+		//so no existing account falls below it. Owner only.
+		#[weight = 10_000]
+		fn set_min_balance(origin, #[compact] min_balance: u64) -> DispatchResult {
+			Self::ensure_not_halted()?;
+			Self::ensure_owner(origin)?;
+			ensure!(min_balance <= Self::get_min_balance(), MyError::<T>::MinBalanceRaised);
+
+			MinBalance::put(min_balance);
+			Self::deposit_event(RawEvent::MinBalanceSet(min_balance));
+			Ok(())
+		}
+
+		//Chooses whether balances left below MinBalance go to the treasury or are burned.
+		//Owner only.
+		#[weight = 10_000]
+		fn set_dust_policy(origin, policy: DustPolicy) -> DispatchResult {
+			Self::ensure_not_halted()?;
+			Self::ensure_owner(origin)?;
+
+			DustPolicyOf::put(policy);
+			Self::deposit_event(RawEvent::DustPolicySet(policy));
+			Ok(())
+		}
+
+		//Spends 'amount' of the treasury's tokens on 'dest'. Owner only.
+		#[weight = 10_000]
+		fn treasury_transfer(origin, dest: <T::Lookup as StaticLookup>::Source, #[compact] amount: u64) -> DispatchResult {
+			Self::ensure_not_halted()?;
+			let owner = Self::ensure_owner(origin)?;
+			let dest = T::Lookup::lookup(dest)?;
+
+			Self::do_transfer(&Self::treasury_account(), &dest, amount, false)?;
+			Self::deposit_event(RawEvent::TreasurySpent(owner, dest, amount));
+			Ok(())
+		}
+
+		//Returns the tokens held by the treasury account
+		#[weight = 10_000]
+		fn treasury_balance_of(origin) -> DispatchResult {
+			Self::ensure_not_halted()?;
+			let user = ensure_signed(origin)?;
+			Self::deposit_event(RawEvent::TreasuryBalanceReturned(user, Self::treasury_account(), Self::treasury_balance()));
+			Ok(())
+		}
+
+		//Sends tokens that ended up in the pallet account by mistake to 'beneficiary'. Only what
+		//exceeds the Earmarked funds the pallet owes to others can be taken. Owner or root.
+		#[weight = 10_000]
+		fn recover_stranded(origin, beneficiary: <T::Lookup as StaticLookup>::Source, #[compact] amount: u64) -> DispatchResult {
+			Self::ensure_not_halted()?;
+			Self::ensure_owner_or_root(origin)?;
+			let beneficiary = T::Lookup::lookup(beneficiary)?;
+			ensure!(amount > 0, MyError::<T>::ZeroAmount);
+
+			let stranded = Self::free_balance(&Self::account_id()).saturating_sub(Self::get_earmarked());
+			ensure!(amount <= stranded, MyError::<T>::NothingToRecover);
+
+			Self::do_move(&Self::account_id(), &beneficiary, amount, false)?;
+			Self::deposit_event(RawEvent::StrandedRecovered(beneficiary, amount));
+			Ok(())
+		}
+
+		//Walks up to AuditBatchSize balances, continuing where the previous call stopped, and
+		//reports the computed total against TotalIssuance, both in shares, once every holder has been visited.
+		//Balances that change while an audit spans several blocks are counted as last seen.
+		//Owner or root.
+		#[weight = 10_000 * (T::AuditBatchSize::get() as u64 + 1)]
+		fn audit_supply(origin) -> DispatchResultWithPostInfo {
+			Self::ensure_not_halted()?;
+			Self::ensure_owner_or_root(origin)?;
+
+			let prefix = <Balances<T>>::final_prefix();
+			let mut cursor = Self::audit_cursor().unwrap_or_else(|| prefix.to_vec());
+			let (mut computed_total, mut holder_count) = Self::audit_progress();
+			let mut visited: u32 = 0;
+			let mut finished = false;
+
+			while visited < T::AuditBatchSize::get() {
+				match sp_io::storage::next_key(&cursor).filter(|key| key.starts_with(&prefix)) {
+					Some(key) => {
+						let balance = unhashed::get::<u64>(&key).unwrap_or(0);
+						computed_total = computed_total.saturating_add(balance as u128);
+						holder_count = holder_count.saturating_add(1);
+						visited += 1;
+						cursor = key;
+					}
+					None => {
+						finished = true;
+						break;
+					}
+				}
+			}
+
+			if finished {
+				AuditCursor::kill();
+				AuditProgress::kill();
+
+				//Reserved shares are not in Balances, they are counted through TotalReserved
+				let computed_total = computed_total.saturating_add(Self::get_total_reserved_shares() as u128);
+				let recorded_total = Self::get_total_shares();
+				Self::deposit_event(RawEvent::AuditCompleted(computed_total, recorded_total, holder_count));
+				if computed_total != recorded_total as u128 {
+					Self::deposit_event(RawEvent::AuditMismatch(computed_total, recorded_total));
+				}
+			} else {
+				AuditCursor::put(cursor);
+				AuditProgress::put((computed_total, holder_count));
+			}
+
+			Ok(Some(10_000 * (visited as u64 + 1)).into())
+		}
+	}
+}
+
+impl<T: Config> Module<T> {
+	//Issues the configured per-block inflation, never beyond the cap. Returns the weight used.
+	fn apply_inflation(now: T::BlockNumber) -> Weight {
+		if Self::get_halted() {
+			return T::DbWeight::get().reads(1);
+		}
+		let config = match Self::inflation() {
+			Some(config) if config.end_block.map_or(true, |end| now <= end) => config,
+			_ => return T::DbWeight::get().reads(2),
+		};
+
+		let headroom = Self::get_max_supply().saturating_sub(Self::get_total_issuance());
+		let amount = config.per_block.min(headroom);
+		if amount == 0 || Self::issue(&config.beneficiary, amount).is_err() {
+			return T::DbWeight::get().reads(4);
+		}
+		T::DbWeight::get().reads_writes(6, 3)
+	}
+
+	//Forgets the transfer_idempotent references whose retention ends at 'now'
+	fn prune_references(now: T::BlockNumber) -> Weight {
+		let expiring = <ReferenceExpiries<T>>::take(now);
+		let count = expiring.len() as u64;
+		for (who, reference) in expiring {
+			//A reference reused after a lazy expiry has a newer entry that must stay
+			if Self::used_references(&who, &reference).map_or(false, |used_at| {
+				used_at.saturating_add(T::ReferenceRetention::get()) <= now
+			}) {
+				<UsedReferences<T>>::remove(&who, &reference);
+			}
+		}
+		T::DbWeight::get().reads_writes(count + 1, count + 1)
+	}
+
+	//Runs the transfers scheduled for 'now'. Each one first releases its reservation; if the
+	//reservation was slashed in the meantime, or the transfer itself fails, the event says so
+	//and whatever was released stays with the sender. While halted the queue moves to the
+	//following blocks, spilling over into later ones where a block is already full.
+	fn execute_scheduled(now: T::BlockNumber) -> Weight {
+		let queue = <ScheduleQueue<T>>::take(now);
+		let count = queue.len() as u64;
+		if count > 0 && Self::get_halted() {
+			//At least one per block, so a lowered cap cannot stall the walk
+			let max = (T::MaxScheduledPerBlock::get() as usize).max(1);
+			let mut next = now.saturating_add(1u32.into());
+			let mut room = max.saturating_sub(Self::schedule_queue(next).len());
+			let mut reads = count + 3;
+			for id in queue {
+				if let Some(mut scheduled) = Self::scheduled(id) {
+					while room == 0 {
+						next = next.saturating_add(1u32.into());
+						room = max.saturating_sub(Self::schedule_queue(next).len());
+						reads += 1;
+					}
+					room -= 1;
+					scheduled.at = next;
+					<Scheduled<T>>::insert(id, scheduled);
+					<ScheduleQueue<T>>::append(next, id);
+				}
+			}
+			return T::DbWeight::get().reads_writes(reads, count * 2 + 1);
+		}
+
+		for id in queue {
+			let scheduled = match <Scheduled<T>>::take(id) {
+				Some(scheduled) => scheduled,
+				None => continue,
+			};
+			let released = Self::unreserve(&scheduled.from, scheduled.value);
+			let result = if released < scheduled.value {
+				Err(MyError::<T>::ReservationSlashed.into())
+			} else {
+				Self::try_batch_item(|| Self::do_transfer(&scheduled.from, &scheduled.dest, scheduled.value, false))
+			};
+			match result {
+				Ok(()) => Self::deposit_event(RawEvent::ScheduledExecuted(id)),
+				Err(error) => Self::deposit_event(RawEvent::ScheduledFailed(id, error)),
+			}
+		}
+		T::DbWeight::get().reads_writes(1 + count * 8, 1 + count * 6)
+	}
+
+	//Fails while root has the pallet halted
+	fn ensure_not_halted() -> DispatchResult {
+		ensure!(!Self::get_halted(), MyError::<T>::Halted);
+		Ok(())
+	}
+
+	//Checks that 'origin' is signed by the token owner. Every administrative call goes
+	//through here.
+	fn ensure_owner(origin: T::Origin) -> Result<T::AccountId, DispatchError> {
+		let who = ensure_signed(origin)?;
+		let owner = Self::get_owner().ok_or(MyError::<T>::NoOwner)?;
+		ensure!(owner == who, MyError::<T>::NotOwner);
+		Ok(who)
+	}
+
+	//Accepts root as well as the token owner
+	fn ensure_owner_or_root(origin: T::Origin) -> DispatchResult {
+		if ensure_root(origin.clone()).is_ok() {
+			return Ok(());
+		}
+		Self::ensure_owner(origin).map(|_| ())
+	}
+
+	//Account the pallet holds funds in
+	pub fn account_id() -> T::AccountId {
+		T::ModuleId::get().into_account()
+	}
+
+	//Message an Ethereum key signs to claim for 'who', with the EIP-191 personal_sign
+	//envelope: "\x19Ethereum Signed Message:\n", the payload length in decimal, then the
+	//payload ETH_CLAIM_PREFIX ++ hex(who.encode())
+	pub fn eth_claim_message(who: &T::AccountId) -> Vec<u8> {
+		const HEX: &[u8; 16] = b"0123456789abcdef";
+		let mut payload = ETH_CLAIM_PREFIX.to_vec();
+		for byte in who.encode() {
+			payload.push(HEX[(byte >> 4) as usize]);
+			payload.push(HEX[(byte & 0x0f) as usize]);
+		}
+
+		let mut length = Vec::new();
+		let mut remaining = payload.len();
+		loop {
+			length.insert(0, b'0' + (remaining % 10) as u8);
+			remaining /= 10;
+			if remaining == 0 {
+				break;
+			}
+		}
+
+		let mut message = b"\x19Ethereum Signed Message:\n".to_vec();
+		message.extend_from_slice(&length);
+		message.extend_from_slice(&payload);
+		message
+	}
+
+	//Ethereum address whose key made 'signature' over the claim message of 'who'
+	fn eth_recover(signature: &[u8; 65], who: &T::AccountId) -> Option<EthereumAddress> {
+		let hash = sp_io::hashing::keccak_256(&Self::eth_claim_message(who));
+		let public = sp_io::crypto::secp256k1_ecdsa_recover(signature, &hash).ok()?;
+		let mut address = EthereumAddress::default();
+		address.copy_from_slice(&sp_io::hashing::keccak_256(&public)[12..]);
+		Some(address)
+	}
+
+	//Root of the merkle tree holding 'leaf' as 'proof' describes it. Each pair of nodes is
+	//hashed in sorted order, so proofs carry no left or right flags.
+	fn merkle_root_of(leaf: [u8; 32], proof: &[[u8; 32]]) -> [u8; 32] {
+		proof.iter().fold(leaf, |node, sibling| {
+			let (first, second) = if node <= *sibling { (node, *sibling) } else { (*sibling, node) };
+			let mut pair = [0u8; 64];
+			pair[..32].copy_from_slice(&first);
+			pair[32..].copy_from_slice(&second);
+			sp_io::hashing::blake2_256(&pair)
+		})
+	}
+
+	//Sub-account collecting the protocol's own funds, such as transfer fees
+	pub fn treasury_account() -> T::AccountId {
+		T::ModuleId::get().into_sub_account(b"treasury")
+	}
+
+	//Tokens held by the treasury account
+	pub fn treasury_balance() -> u64 {
+		Self::free_balance(&Self::treasury_account())
+	}
+
+	//Sub-account holding the native currency raised by the crowdsale
+	pub fn sale_account() -> T::AccountId {
+		T::ModuleId::get().into_sub_account(b"sale")
+	}
+
+	//Sub-account holding the native currency backing wrapped tokens
+	pub fn wrap_account() -> T::AccountId {
+		T::ModuleId::get().into_sub_account(b"wrap")
+	}
+
+	//Pays an escrow out of the pallet account to 'to' and forgets it
+	fn release_escrow(id: u64, escrow: &EscrowOf<T>, to: &T::AccountId) -> DispatchResult {
+		Self::pay_from_pallet(to, escrow.value)?;
+		<Escrows<T>>::remove(id);
+		Ok(())
+	}
+
+	//Shares that earn dividends: everything in free balances outside the pallet account
+	fn dividend_supply() -> u64 {
+		Self::get_total_shares()
+			.saturating_sub(Self::get_total_reserved_shares())
+			.saturating_sub(<Balances<T>>::get(Self::account_id()))
+	}
+
+	//Dividends 'who' has accrued so far but not claimed yet
+	pub fn withdrawable_dividend_of(who: &T::AccountId) -> u64 {
+		let magnified = (Self::magnified_dividend_per_share().saturating_mul(<Balances<T>>::get(who) as u128) as i128)
+			.saturating_add(Self::dividend_corrections(who));
+		let accumulated = (magnified.max(0) as u128 / DIVIDEND_MAGNITUDE) as u64;
+		accumulated.saturating_sub(Self::withdrawn_dividends(who))
+	}
+
+	//Keeps the dividends accrued by 'who' unchanged when its shares move from 'old' to 'new',
+	//so shares only earn from the distributions funded while they are held
+	fn correct_dividends(who: &T::AccountId, old: u64, new: u64) {
+		let per_share = Self::magnified_dividend_per_share();
+		if per_share == 0 || who == &Self::account_id() {
+			return;
+		}
+		<DividendCorrections<T>>::mutate(who, |correction| {
+			if new > old {
+				*correction = correction.saturating_sub(per_share.saturating_mul((new - old) as u128) as i128);
+			} else {
+				*correction = correction.saturating_add(per_share.saturating_mul((old - new) as u128) as i128);
+			}
+		});
+	}
+
+	//Issues the staking rewards of the blocks since the last update into the pallet account
+	//and spreads them over the staked tokens. Rewards stop once the cap is reached.
+	fn update_reward_pool() {
+		let now = frame_system::Module::<T>::block_number();
+		let last = Self::last_reward_block();
+		if now <= last {
+			return;
+		}
+		LastRewardBlock::<T>::put(now);
+		let total_staked = Self::total_staked();
+		if total_staked == 0 {
+			return;
+		}
+
+		let blocks: u64 = now.saturating_sub(last).saturated_into();
+		let headroom = Self::get_max_supply().saturating_sub(Self::get_total_issuance());
+		let reward = Self::staking_reward_per_block().saturating_mul(blocks).min(headroom);
+		if reward == 0 || Self::issue(&Self::account_id(), reward).is_err() {
+			return;
+		}
+		Earmarked::mutate(|earmarked| *earmarked = earmarked.saturating_add(reward));
+		RewardPerShare::mutate(|per_share| {
+			*per_share = per_share.saturating_add(reward as u128 * REWARD_PRECISION / total_staked as u128)
+		});
+	}
+
+	//Pays 'who' the rewards its stake earned since its last claim. Returns the amount paid.
+	fn pay_rewards(who: &T::AccountId) -> Result<u64, DispatchError> {
+		let mut stake = Self::stakes(who);
+		if stake.amount == 0 {
+			return Ok(0);
+		}
+		let accrued = stake.amount as u128 * Self::reward_per_share() / REWARD_PRECISION;
+		let pending = accrued.saturating_sub(stake.reward_debt) as u64;
+		//Pay before recording the debt, so a failed payout leaves the rewards claimable
+		if pending > 0 {
+			Self::pay_from_pallet(who, pending)?;
+			Self::deposit_event(RawEvent::RewardsClaimed(who.clone(), pending));
+		}
+		stake.reward_debt = accrued;
+		<Stakes<T>>::insert(who, stake);
+		Ok(pending)
+	}
+
+	//Pays 'amount' of the earmarked funds in the pallet account out to 'to'
+	fn pay_from_pallet(to: &T::AccountId, amount: u64) -> DispatchResult {
+		if amount > 0 {
+			Self::do_move(&Self::account_id(), to, amount, false)?;
+			Earmarked::mutate(|earmarked| *earmarked = earmarked.saturating_sub(amount));
+		}
+		Ok(())
+	}
+
+	//Checks that 'origin' is signed by the owner or by an account holding 'role'. Roles stop
+	//working once ownership is renounced.
+	fn ensure_owner_or_role(origin: T::Origin, role: Role) -> Result<T::AccountId, DispatchError> {
+		let who = ensure_signed(origin)?;
+		let owner = Self::get_owner().ok_or(MyError::<T>::NoOwner)?;
+		ensure!(owner == who || Self::has_role(&who, role), MyError::<T>::MissingRole);
+		Ok(who)
+	}
+
+	pub fn has_role(who: &T::AccountId, role: Role) -> bool {
+		Self::get_roles(who) & role.bit() != 0
+	}
+
+	//Takes a provider reference for every existing holder
+	fn migrate_to_v2() -> Weight {
+		let mut holders: u64 = 0;
+		for (who, _) in <Balances<T>>::iter() {
+			frame_system::Module::<T>::inc_providers(&who);
+			holders += 1;
+		}
+		StorageVersion::put(Releases::V2);
+		T::DbWeight::get().reads_writes(holders, holders + 1)
+	}
+
+	//Wraps every stored allowance amount in an Allowance record without restrictions
+	fn migrate_to_v3() -> Weight {
+		let mut entries: u64 = 0;
+		<Allowances<T>>::translate::<u64, _>(|_, _, amount| {
+			entries += 1;
+			Some(Allowance::new(amount))
+		});
+		StorageVersion::put(Releases::V3);
+		T::DbWeight::get().reads_writes(entries, entries + 1)
+	}
+
+	//Gives every existing holder the voting power of its balance, delegated to itself
+	fn migrate_to_v4() -> Weight {
+		let now = frame_system::Module::<T>::block_number();
+		let mut holders: u64 = 0;
+		for (who, balance) in <Balances<T>>::iter() {
+			<Checkpoints<T>>::insert(&who, sp_std::vec![(now, balance)]);
+			holders += 1;
+		}
+		StorageVersion::put(Releases::V4);
+		T::DbWeight::get().reads_writes(holders + 1, holders + 1)
+	}
+
+	//Turns raw balances into shares. The factor starts at SCALING_PRECISION, where one share
+	//is one token, so every stored amount is already the right number of shares.
+	fn migrate_to_v5() -> Weight {
+		ScalingFactor::put(SCALING_PRECISION);
+		StorageVersion::put(Releases::V5);
+		T::DbWeight::get().writes(2)
+	}
+
+	//Drops the collector from the transfer fee configuration; fees now go to the treasury
+	fn migrate_to_v6() -> Weight {
+		let _ = TransferFeeConfig::translate::<(u16, T::AccountId), _>(|old| {
+			old.map(|(rate_bps, _collector)| TransferFee { rate_bps })
+		});
+		StorageVersion::put(Releases::V6);
+		T::DbWeight::get().reads_writes(1, 2)
+	}
+
+	//Fills TotalIssuance and TotalReserved from the balances and reserves held before they
+	//were tracked, so the recorded supply matches what accounts actually hold
+	fn migrate_to_v7() -> Weight {
+		let mut entries: u64 = 0;
+		let mut held: u64 = 0;
+		for (_, balance) in <Balances<T>>::iter() {
+			held = held.saturating_add(balance);
+			entries += 1;
+		}
+		let mut reserved: u64 = 0;
+		for (_, amount) in <Reserved<T>>::iter() {
+			reserved = reserved.saturating_add(amount);
+			entries += 1;
+		}
+		TotalIssuance::put(held.saturating_add(reserved));
+		TotalReserved::put(reserved);
+		StorageVersion::put(Releases::V7);
+		T::DbWeight::get().reads_writes(entries, 3)
+	}
+
+	//Checks the invariants the pallet relies on: balances add up to TotalIssuance, no
+	//zero entries are kept and a minted token has a ticker and a name.
+	#[cfg(any(feature = "try-runtime", test))]
+	pub fn do_try_state() -> Result<(), &'static str> {
+		let mut total: u64 = 0;
+		for (_, balance) in <Balances<T>>::iter() {
+			if balance == 0 {
+				return Err("zero balance entry left in storage");
+			}
+			total = total.checked_add(balance).ok_or("sum of balances overflows")?;
+		}
+		let mut reserved: u64 = 0;
+		for (_, amount) in <Reserved<T>>::iter() {
+			reserved = reserved.checked_add(amount).ok_or("sum of reserved balances overflows")?;
+		}
+		if reserved != Self::get_total_reserved_shares() {
+			return Err("sum of reserved balances does not match TotalReserved");
+		}
+		if total.checked_add(reserved) != Some(Self::get_total_shares()) {
+			return Err("sum of balances does not match TotalIssuance");
+		}
+
+		//Native currency sent straight to the wrap account adds to its holdings without
+		//wrapping, so only a shortfall breaks the backing
+		let backing: u64 = T::Currency::total_balance(&Self::wrap_account()).saturated_into();
+		if backing < Self::wrapped_supply() {
+			return Err("wrapped tokens are not backed by the wrap account's native holdings");
+		}
+
+		if Self::get_mint() && (Self::get_ticker().is_empty() || Self::get_name().is_empty()) {
+			return Err("minted token is missing its ticker or name");
+		}
+		Ok(())
+	}
+
+	//Moves value from 'from' to 'to' for every transfer path that carries no memo, so
+	//recipients that flagged themselves with require_memo are refused
+	fn do_transfer(from: &T::AccountId, to: &T::AccountId, value: u64, keep_alive: bool) -> DispatchResult {
+		Self::do_transfer_by(from, from, to, value, keep_alive)
+	}
+
+	//Transfers value like do_transfer and returns what 'to' actually received, after any fee
+	//or burn. Calls that lock, hold or pass on the tokens work with this amount, so they
+	//never count tokens that did not arrive.
+	fn transfer_received(from: &T::AccountId, to: &T::AccountId, value: u64) -> Result<u64, DispatchError> {
+		let before = Self::free_balance(to);
+		Self::do_transfer(from, to, value, false)?;
+		Ok(Self::free_balance(to).saturating_sub(before))
+	}
+
+	//Like do_transfer, with 'operator' being whoever initiated the move: the sender itself,
+	//or the spender of an allowance
+	fn do_transfer_by(operator: &T::AccountId, from: &T::AccountId, to: &T::AccountId, value: u64, keep_alive: bool) -> DispatchResult {
+		ensure!(!Self::memo_required(to), MyError::<T>::MemoRequired);
+		Self::move_balance(operator, from, to, value, keep_alive)
+	}
+
+	//Moves value from 'from' to 'to' on the initiative of 'operator', once the pause and
+	//every account restriction have been checked
+	fn move_balance(operator: &T::AccountId, from: &T::AccountId, to: &T::AccountId, value: u64, keep_alive: bool) -> DispatchResult {
+		ensure!(!Self::get_paused(), MyError::<T>::TokenPaused);
+		ensure!(!Self::import_open(), MyError::<T>::ImportIsOpen);
+		Self::ensure_transferable(operator)?;
+		Self::ensure_can_move(from, to)?;
+		Self::ensure_within_transfer_bounds(from, value)?;
+		Self::ensure_volume_available(value)?;
+		Self::charge_and_move(from, to, value, keep_alive)
+	}
+
+	//Charges the sender's transfer limit and the transfer fee and burn, then moves what is
+	//left of value to 'to', as long as the sender's locks stay covered; or does none of it
+	#[transactional]
+	fn charge_and_move(from: &T::AccountId, to: &T::AccountId, value: u64, keep_alive: bool) -> DispatchResult {
+		ensure!(value > 0, MyError::<T>::ZeroAmount);
+		ensure!(from != to, MyError::<T>::SelfTransfer);
+		Self::charge_transfer_limit(from, value)?;
+		let deducted = Self::take_deductions(from, to, value)?;
+		if deducted < value {
+			Self::do_move(from, to, value - deducted, keep_alive)?;
+		} else {
+			//Nothing is left for the recipient
+			ensure!(!keep_alive || Self::free_balance(from) >= Self::get_min_balance().max(1), MyError::<T>::WouldKill);
+		}
+		Self::ensure_locks_respected(from)?;
+		Self::sweep_dust(from)
+	}
+
+	//Sweeps the balance of 'who' according to the DustPolicy when it is left above zero but
+	//below MinBalance. Pallet accounts and accounts with locked tokens are never swept.
+	fn sweep_dust(who: &T::AccountId) -> DispatchResult {
+		let amount = Self::free_balance(who);
+		if amount == 0 || amount >= Self::get_min_balance() || Self::locked_balance(who) > 0 {
+			return Ok(());
+		}
+		let treasury = Self::treasury_account();
+		if who == &treasury || who == &Self::account_id() {
+			return Ok(());
+		}
+
+		let shares = <Balances<T>>::get(who);
+		Self::try_mutate_balance(who, |balance| -> DispatchResult {
+			*balance = 0;
+			Ok(())
+		})?;
+		match Self::dust_policy() {
+			DustPolicy::Treasury => Self::try_mutate_balance(&treasury, |balance| -> DispatchResult {
+				*balance = balance.checked_add(shares).ok_or(MyError::<T>::Overflow)?;
+				Ok(())
+			})?,
+			DustPolicy::Burn => TotalIssuance::mutate(|issuance| *issuance = issuance.saturating_sub(shares)),
+		}
+		Self::deposit_event(RawEvent::DustLost(who.clone(), amount));
+		Ok(())
+	}
+
+	//Fee and burn owed on a transfer of 'value' from 'from' to 'to'; none when either side is
+	//exempt or the treasury itself. The fee goes to the treasury. It is taken first and the
+	//burn is capped at what remains, so together they never exceed value.
+	fn deductions_on(from: &T::AccountId, to: &T::AccountId, value: u64) -> (Option<(T::AccountId, u64)>, u64) {
+		//Without a fee or a burn there is nothing to deduct, so skip the exemption reads
+		let fee_config = Self::transfer_fee();
+		let burn_rate_bps = Self::burn_rate_bps();
+		if fee_config.is_none() && burn_rate_bps == 0 {
+			return (None, 0);
+		}
+		let treasury = Self::treasury_account();
+		if Self::deduction_exempt(from) || Self::deduction_exempt(to) || from == &treasury || to == &treasury {
+			return (None, 0);
+		}
+		let fee = fee_config
+			.map(|config| config.fee_on(value))
+			.filter(|fee| *fee > 0)
+			.map(|fee| (treasury, fee));
+		let left = value - fee.as_ref().map_or(0, |(_, fee)| *fee);
+		let burn = (value as u128 * burn_rate_bps as u128 / 10_000) as u64;
+		(fee, burn.min(left))
+	}
+
+	//Debits 'from' for the fee and burn on 'value' and settles them. Returns the amount taken.
+	fn take_deductions(from: &T::AccountId, to: &T::AccountId, value: u64) -> Result<u64, DispatchError> {
+		let (fee, burn) = Self::deductions_on(from, to, value);
+		let deducted = fee.as_ref().map_or(0, |(_, fee)| *fee) + burn;
+		if deducted == 0 {
+			return Ok(0);
+		}
+
+		let shares = Self::deduction_shares(&fee, burn);
+		Self::try_mutate_balance(from, |balance| -> DispatchResult {
+			*balance = balance.checked_sub(shares).ok_or(MyError::<T>::NotEnoughFunds)?;
+			Ok(())
+		})?;
+		Self::settle_deductions(from, fee, burn)?;
+		Ok(deducted)
+	}
+
+	//Shares debited for a fee and a burn; settle_deductions hands exactly these on
+	fn deduction_shares(fee: &Option<(T::AccountId, u64)>, burn: u64) -> u64 {
+		fee.as_ref().map_or(0, |(_, fee)| Self::to_shares(*fee)).saturating_add(Self::to_shares(burn))
+	}
+
+	//Credits the fee to its collector and destroys the burned part, both already debited
+	//from 'from'
+	fn settle_deductions(from: &T::AccountId, fee: Option<(T::AccountId, u64)>, burn: u64) -> DispatchResult {
+		if let Some((collector, fee)) = fee {
+			let shares = Self::to_shares(fee);
+			Self::try_mutate_balance(&collector, |balance| -> DispatchResult {
+				*balance = balance.checked_add(shares).ok_or(MyError::<T>::Overflow)?;
+				Ok(())
+			})?;
+			Self::deposit_event(RawEvent::FeeCharged(from.clone(), collector, fee));
+		}
+		if burn > 0 {
+			let shares = Self::to_shares(burn);
+			TotalIssuance::mutate(|issuance| *issuance = issuance.saturating_sub(shares));
+			Self::deposit_event(RawEvent::Burned(from.clone(), burn));
+		}
+		Ok(())
+	}
+
+	//Trips the circuit breaker, pausing the token, when moving value would take this block's
+	//volume past MaxVolumePerBlock. Callers must not run inside a storage transaction that the
+	//resulting error rolls back, or the pause is lost.
+	fn ensure_volume_available(value: u64) -> DispatchResult {
+		if let Some(max) = Self::max_volume_per_block() {
+			let volume = Self::block_volume().saturating_add(value);
+			if volume > max {
+				Paused::put(true);
+				BreakerTripped::put(true);
+				let now = frame_system::Module::<T>::block_number();
+				Self::deposit_event(RawEvent::CircuitBreakerTripped(now, volume));
+				return Err(MyError::<T>::CircuitBreaker.into());
+			}
+		}
+		Ok(())
+	}
+
+	//Checks a single transfer amount against MaxTransferAmount and MinTransferAmount. Moving
+	//the sender's whole balance is always allowed so accounts can be closed.
+	fn ensure_within_transfer_bounds(from: &T::AccountId, value: u64) -> DispatchResult {
+		if let Some(max) = Self::max_transfer_amount() {
+			ensure!(value <= max, MyError::<T>::AboveMaxTransfer);
+		}
+		let min = Self::min_transfer_amount();
+		if min > 0 && value < min {
+			ensure!(value == Self::free_balance(from), MyError::<T>::BelowMinTransfer);
+		}
+		Ok(())
+	}
+
+	//Counts value against the transfer limit 'who' set for itself, if any
+	fn charge_transfer_limit(who: &T::AccountId, value: u64) -> DispatchResult {
+		let now = frame_system::Module::<T>::block_number();
+		Self::apply_pending_limit(who, now);
+		<TransferLimits<T>>::try_mutate_exists(who, |limit| -> DispatchResult {
+			if let Some(limit) = limit {
+				ensure!(limit.try_spend(now, value), MyError::<T>::TransferLimitExceeded);
+			}
+			Ok(())
+		})
+	}
+
+	//Puts a scheduled loosening or removal of the transfer limit of 'who' in place once its
+	//delay has passed. The current window and what was spent in it carry over.
+	fn apply_pending_limit(who: &T::AccountId, now: T::BlockNumber) {
+		match Self::pending_transfer_limit(who) {
+			Some((_, _, effective_at)) if now < effective_at => (),
+			Some((max_per_period, period_blocks, _)) => {
+				<PendingTransferLimit<T>>::remove(who);
+				if max_per_period == 0 {
+					<TransferLimits<T>>::remove(who);
+				} else {
+					let current = Self::transfer_limits(who).unwrap_or_else(|| RateLimit::new(0, period_blocks, now));
+					<TransferLimits<T>>::insert(who, RateLimit { max_per_period, period_blocks, ..current });
+				}
+			}
+			None => (),
+		}
+	}
+
+	//Whether 'who' holds tokens or was pre-created with touch
+	fn has_account(who: &T::AccountId) -> bool {
+		<Balances<T>>::contains_key(who) || <Touched<T>>::contains_key(who)
+	}
+
+	//Moves value from 'from' to 'to' without any policy check. Both balances are updated
+	//inside one mutation so a failed check leaves storage untouched. With 'keep_alive' the
+	//sender must be left with at least MinBalance, and never with nothing. A recipient without
+	//an account must receive at least MinBalance.
+	fn do_move(from: &T::AccountId, to: &T::AccountId, value: u64, keep_alive: bool) -> DispatchResult {
+		ensure!(value > 0, MyError::<T>::ZeroAmount);
+		ensure!(from != to, MyError::<T>::SelfTransfer);
+		ensure!(<Balances<T>>::contains_key(from), MyError::<T>::NoValueStored);
+		ensure!(Self::has_account(to) || value >= Self::get_min_balance(), MyError::<T>::BelowMinimum);
+		//At a large scaling factor an amount can be worth less than one share
+		let shares = Self::shares_for_debit(from, value);
+		ensure!(shares > 0, MyError::<T>::ZeroAmount);
+
+		Self::try_mutate_balance(from, |from_balance| -> DispatchResult {
+			*from_balance = from_balance.checked_sub(shares).ok_or(MyError::<T>::NotEnoughFunds)?;
+			if keep_alive {
+				ensure!(*from_balance > 0 && Self::to_display(*from_balance) >= Self::get_min_balance(), MyError::<T>::WouldKill);
+			}
+			Self::try_mutate_balance(to, |to_balance| -> DispatchResult {
+				*to_balance = to_balance.checked_add(shares).ok_or(MyError::<T>::Overflow)?;
+				Ok(())
+			})
+		})?;
+
+		Self::deposit_transfer(from, to, value);
+		Ok(())
+	}
+
+	//Announces a completed transfer under the next transfer id and adds it to this block's
+	//volume
+	fn deposit_transfer(from: &T::AccountId, to: &T::AccountId, value: u64) {
+		let id = TransferCounter::mutate(|counter| {
+			let id = *counter;
+			*counter = counter.saturating_add(1);
+			id
+		});
+		BlockVolume::mutate(|volume| *volume = volume.saturating_add(value));
+		Self::deposit_event(RawEvent::Transfer(id, from.clone(), to.clone(), value));
+	}
+
+	//Refuses to move funds between blacklisted accounts, between accounts off the whitelist
+	//when the policy requires it, out of a frozen account, or into one frozen with
+	//'block_incoming'
+	fn ensure_can_move(from: &T::AccountId, to: &T::AccountId) -> DispatchResult {
+		ensure!(!Self::is_blacklisted(from), MyError::<T>::SenderBlacklisted);
+		ensure!(!Self::is_blacklisted(to), MyError::<T>::RecipientBlacklisted);
+		if Self::transfer_policy() == TransferPolicy::WhitelistOnly {
+			ensure!(Self::whitelisted(from) && Self::whitelisted(to), MyError::<T>::NotWhitelisted);
+		}
+		ensure!(!Self::is_frozen(from), MyError::<T>::AccountFrozen);
+		ensure!(Self::frozen_accounts(to) != Some(true), MyError::<T>::AccountFrozen);
+		Ok(())
+	}
+
+	//While the token is soulbound only exempt accounts may initiate transfers
+	fn ensure_transferable(operator: &T::AccountId) -> DispatchResult {
+		ensure!(!Self::get_soulbound() || Self::soulbound_exempt(operator), MyError::<T>::NonTransferable);
+		Ok(())
+	}
+
+	pub fn is_frozen(who: &T::AccountId) -> bool {
+		<FrozenAccounts<T>>::contains_key(who)
+	}
+
+	pub fn is_blacklisted(who: &T::AccountId) -> bool {
+		<Blacklist<T>>::contains_key(who)
+	}
+
+	//Debits 'from' once for the sum of the batch, then credits each recipient in order, less
+	//the transfer fee and burn charged per item
+	#[transactional]
+	fn do_batch_transfer(from: &T::AccountId, recipients: &[(T::AccountId, u64)]) -> DispatchResult {
+		ensure!(!recipients.is_empty(), MyError::<T>::EmptyBatch);
+		ensure!(!Self::import_open(), MyError::<T>::ImportIsOpen);
+		Self::ensure_transferable(from)?;
+		let mut total: u64 = 0;
+		for (to, value) in recipients {
+			ensure!(*value > 0, MyError::<T>::ZeroAmount);
+			ensure!(to != from, MyError::<T>::SelfTransfer);
+			ensure!(!Self::memo_required(to), MyError::<T>::MemoRequired);
+			Self::ensure_can_move(from, to)?;
+			Self::ensure_within_transfer_bounds(from, *value)?;
+			total = total.checked_add(*value).ok_or(MyError::<T>::Overflow)?;
+		}
+		ensure!(<Balances<T>>::contains_key(from), MyError::<T>::NoValueStored);
+		Self::charge_transfer_limit(from, total)?;
+
+		//The debit is the sum of the shares handed on below, so no share is created or lost
+		let mut shares: u64 = 0;
+		for (to, value) in recipients {
+			let (fee, burn) = Self::deductions_on(from, to, *value);
+			let net = *value - fee.as_ref().map_or(0, |(_, fee)| *fee) - burn;
+			shares = shares.saturating_add(Self::deduction_shares(&fee, burn)).saturating_add(Self::to_shares(net));
+		}
+		Self::try_mutate_balance(from, |balance| -> DispatchResult {
+			*balance = balance.checked_sub(shares).ok_or(MyError::<T>::NotEnoughFunds)?;
+			Ok(())
+		})?;
+		Self::ensure_locks_respected(from)?;
+		for (to, value) in recipients {
+			let (fee, burn) = Self::deductions_on(from, to, *value);
+			let net = *value - fee.as_ref().map_or(0, |(_, fee)| *fee) - burn;
+			Self::settle_deductions(from, fee, burn)?;
+			if net > 0 {
+				ensure!(Self::has_account(to) || net >= Self::get_min_balance(), MyError::<T>::BelowMinimum);
+				let shares = Self::to_shares(net);
+				Self::try_mutate_balance(to, |balance| -> DispatchResult {
+					*balance = balance.checked_add(shares).ok_or(MyError::<T>::Overflow)?;
+					Ok(())
+				})?;
+				Self::deposit_transfer(from, to, net);
+			}
+		}
+		Self::sweep_dust(from)
+	}
+
+	//Runs one item of a best-effort batch, undoing whatever it wrote if it fails
+	fn try_batch_item(item: impl FnOnce() -> DispatchResult) -> DispatchResult {
+		with_transaction(|| match item() {
+			Ok(()) => TransactionOutcome::Commit(Ok(())),
+			Err(error) => TransactionOutcome::Rollback(Err(error)),
+		})
+	}
+
+	//Checks token metadata and returns the ticker normalised to uppercase
+	fn validate_metadata(name: &[u8], mut ticker: Vec<u8>, decimals: u8) -> Result<Vec<u8>, DispatchError> {
+		ensure!(!name.is_empty(), MyError::<T>::EmptyName);
+		ensure!(name.len() <= 64, MyError::<T>::NameTooBig);
+		ensure!(ticker.len() <= 32, MyError::<T>::TickerTooBig);
+		ensure!(!ticker.is_empty() && ticker.iter().all(u8::is_ascii_alphanumeric), MyError::<T>::InvalidTicker);
+		ensure!(decimals <= T::MaxDecimals::get(), MyError::<T>::TooManyDecimals);
+		//One whole token (10^decimals base units) has to fit in a balance
+		ensure!(10u64.checked_pow(decimals as u32).is_some(), MyError::<T>::Overflow);
+
+		ticker.make_ascii_uppercase();
+		Ok(ticker)
+	}
+
+	//Sets up the token with a cap of 'supply', owned by 'creator', and issues 'allocations';
+	//or, when any allocation fails, does none of it
+	#[transactional]
+	fn do_mint(
+		creator: T::AccountId,
+		name: Vec<u8>,
+		ticker: Vec<u8>,
+		supply: u64,
+		decimals: u8,
+		min_balance: u64,
+		allocations: &[(T::AccountId, u64)],
+	) -> DispatchResult {
+		ensure!(Self::get_mint() == false, MyError::<T>::AlreadyMinted);
+		ensure!(supply > 0, MyError::<T>::ZeroSupply);
+		ensure!(allocations.iter().all(|(_, amount)| *amount == 0 || *amount >= min_balance), MyError::<T>::BelowMinimum);
+		let ticker = Self::validate_metadata(&name, ticker, decimals)?;
+
+		Ticker::put(&ticker);
+		Name::put(name);
+		MaxSupply::put(supply);
+		Decimals::put(decimals);
+		MinBalance::put(min_balance);
+		<Owner<T>>::put(&creator);
+
+		for (who, amount) in allocations {
+			if *amount > 0 {
+				Self::issue(who, *amount)?;
+			}
+		}
+		Minted::put(true);
+
+		Self::deposit_event(RawEvent::OwnerSet(creator.clone()));
+		Self::deposit_event(RawEvent::Minted(creator, ticker, supply));
+		Ok(())
+	}
+
+	//Credits newly created tokens to 'who', keeping TotalIssuance within MaxSupply
+	fn issue(who: &T::AccountId, amount: u64) -> DispatchResult {
+		let shares = Self::to_shares(amount);
+		let total_shares = Self::get_total_shares().checked_add(shares).ok_or(MyError::<T>::Overflow)?;
+		let total_issuance = Self::get_total_issuance().checked_add(amount).ok_or(MyError::<T>::Overflow)?;
+		ensure!(total_issuance <= Self::get_max_supply(), MyError::<T>::CapExceeded);
+
+		Self::try_mutate_balance(who, |balance| -> DispatchResult {
+			*balance = balance.checked_add(shares).ok_or(MyError::<T>::Overflow)?;
+			Ok(())
+		})?;
+		TotalIssuance::put(total_shares);
+
+		Self::deposit_event(RawEvent::Issued(who.clone(), amount));
+		Ok(())
+	}
+
+	//Locks id on 'who' for 'amount' until block 'until', replacing any lock with the same id
+	pub fn set_lock(id: LockIdentifier, who: &T::AccountId, amount: u64, until: Option<T::BlockNumber>) -> DispatchResult {
+		ensure!(amount > 0, MyError::<T>::ZeroAmount);
+		<Locks<T>>::try_mutate(who, |locks| -> DispatchResult {
+			let now = frame_system::Module::<T>::block_number();
+			locks.retain(|lock| lock.id != id && lock.is_active(&now));
+			ensure!(locks.len() < T::MaxLocks::get() as usize, MyError::<T>::TooManyLocks);
+			locks.push(BalanceLock { id, amount, until });
+			Ok(())
+		})?;
+		Self::deposit_event(RawEvent::LockSet(who.clone(), id, amount, until));
+		Ok(())
+	}
+
+	//Lifts lock id from 'who'
+	pub fn remove_lock(id: LockIdentifier, who: &T::AccountId) {
+		<Locks<T>>::mutate_exists(who, |locks| {
+			if let Some(list) = locks {
+				list.retain(|lock| lock.id != id);
+				if list.is_empty() {
+					*locks = None;
+				}
+			}
+		});
+		Self::deposit_event(RawEvent::LockRemoved(who.clone(), id));
+	}
+
+	//Sets the vesting lock of 'who' to what its schedules still hold locked, dropping the
+	//schedules that finished
+	fn update_vesting_lock(who: &T::AccountId) -> DispatchResult {
+		let now = frame_system::Module::<T>::block_number();
+		let mut schedules = Self::vesting(who);
+		schedules.retain(|schedule| schedule.locked_at(now) > 0);
+		let locked = schedules.iter().fold(0u64, |total, schedule| total.saturating_add(schedule.locked_at(now)));
+
+		if schedules.is_empty() {
+			<Vesting<T>>::remove(who);
+			Self::remove_lock(VESTING_ID, who);
+			Self::deposit_event(RawEvent::VestingCompleted(who.clone()));
+			Ok(())
+		} else {
+			<Vesting<T>>::insert(who, schedules);
+			Self::set_lock(VESTING_ID, who, locked, None)
+		}
+	}
+
+	//Rewrites the time locks of 'who' from its pending transfer_locked entries, forgetting the
+	//ones already unlocked. There is one lock per unlock block, covering every entry that
+	//unlocks at or after it, so that the largest lock in force is always the sum still locked.
+	fn rebuild_time_locks(who: &T::AccountId) -> DispatchResult {
+		let now = frame_system::Module::<T>::block_number();
+		let mut entries = Self::time_locks(who);
+		entries.retain(|(_, unlock_at)| *unlock_at > now);
+
+		<Locks<T>>::try_mutate(who, |locks| -> DispatchResult {
+			locks.retain(|lock| lock.id[..2] != TIME_LOCK_PREFIX && lock.is_active(&now));
+			let mut heights: Vec<T::BlockNumber> = entries.iter().map(|(_, unlock_at)| *unlock_at).collect();
+			heights.sort();
+			heights.dedup();
+			for height in heights {
+				let amount = entries.iter()
+					.filter(|(_, unlock_at)| *unlock_at >= height)
+					.fold(0u64, |total, (amount, _)| total.saturating_add(*amount));
+				ensure!(locks.len() < T::MaxLocks::get() as usize, MyError::<T>::TooManyLocks);
+				locks.push(BalanceLock { id: Self::time_lock_id(height), amount, until: Some(height) });
+			}
+			Ok(())
+		})?;
+
+		if entries.is_empty() {
+			<TimeLocks<T>>::remove(who);
+		} else {
+			<TimeLocks<T>>::insert(who, entries);
+		}
+		Ok(())
+	}
+
+	fn time_lock_id(unlock_at: T::BlockNumber) -> LockIdentifier {
+		let height: u64 = unlock_at.saturated_into();
+		let mut id = [0u8; 8];
+		id[..2].copy_from_slice(&TIME_LOCK_PREFIX);
+		id[2..].copy_from_slice(&height.to_le_bytes()[..6]);
+		id
+	}
+
+	//Balance of 'who' held by its largest lock still in force
+	pub fn locked_balance(who: &T::AccountId) -> u64 {
+		let now = frame_system::Module::<T>::block_number();
+		Self::locks(who).iter().filter(|lock| lock.is_active(&now)).map(|lock| lock.amount).max().unwrap_or(0)
+	}
+
+	//Fails if 'who' no longer holds enough to cover its locks
+	fn ensure_locks_respected(who: &T::AccountId) -> DispatchResult {
+		ensure!(Self::free_balance(who) >= Self::locked_balance(who), MyError::<T>::LiquidityRestrictions);
+		Ok(())
+	}
+
+	//Moves 'amount' of the free balance of 'who' to its reserved balance. Locked tokens
+	//cannot be reserved.
+	pub fn reserve(who: &T::AccountId, amount: u64) -> DispatchResult {
+		ensure!(amount > 0, MyError::<T>::ZeroAmount);
+		let free = Self::free_balance(who).checked_sub(amount).ok_or(MyError::<T>::NotEnoughFunds)?;
+		ensure!(free >= Self::locked_balance(who), MyError::<T>::LiquidityRestrictions);
+		let shares = Self::shares_for_debit(who, amount);
+		let reserved = Self::reserved_shares(who).checked_add(shares).ok_or(MyError::<T>::Overflow)?;
+		let total_reserved = Self::get_total_reserved_shares().checked_add(shares).ok_or(MyError::<T>::Overflow)?;
+
+		//The reserved entry holds its own provider so the account outlives its free balance
+		if reserved == shares {
+			frame_system::Module::<T>::inc_providers(who);
+		}
+		<Reserved<T>>::insert(who, reserved);
+		TotalReserved::put(total_reserved);
+		Self::try_mutate_balance(who, |balance| -> DispatchResult {
+			*balance = balance.saturating_sub(shares);
+			Ok(())
+		})?;
+
+		Self::deposit_event(RawEvent::Reserved(who.clone(), amount));
+		Ok(())
+	}
+
+	//Moves up to 'amount' of the reserved balance of 'who' back to its free balance.
+	//Returns the amount actually unreserved.
+	pub fn unreserve(who: &T::AccountId, amount: u64) -> u64 {
+		let (amount, shares) = Self::reserved_for(who, amount);
+		if amount == 0 {
+			return 0;
+		}
+		if Self::try_mutate_balance(who, |balance| -> DispatchResult {
+			*balance = balance.checked_add(shares).ok_or(MyError::<T>::Overflow)?;
+			Ok(())
+		}).is_err() {
+			return 0;
+		}
+		Self::reduce_reserved(who, shares);
+
+		Self::deposit_event(RawEvent::Unreserved(who.clone(), amount));
+		amount
+	}
+
+	//Destroys up to 'amount' of the reserved balance of 'who', lowering TotalIssuance.
+	//Returns the amount actually slashed.
+	pub fn slash_reserved(who: &T::AccountId, amount: u64) -> u64 {
+		let (amount, shares) = Self::reserved_for(who, amount);
+		if amount == 0 {
+			return 0;
+		}
+		Self::reduce_reserved(who, shares);
+		TotalIssuance::mutate(|issuance| *issuance = issuance.saturating_sub(shares));
+
+		Self::deposit_event(RawEvent::Slashed(who.clone(), amount));
+		amount
+	}
+
+	//Caps 'amount' at the reserved balance of 'who' and returns it with the shares it takes.
+	//Taking the whole reserved balance takes every share.
+	fn reserved_for(who: &T::AccountId, amount: u64) -> (u64, u64) {
+		let reserved = Self::reserved_shares(who);
+		let whole = Self::to_display(reserved);
+		if amount >= whole {
+			(whole, reserved)
+		} else {
+			(amount, Self::to_shares(amount))
+		}
+	}
+
+	//Takes 'shares', which must not exceed them, off the reserved shares of 'who'
+	fn reduce_reserved(who: &T::AccountId, shares: u64) {
+		let reserved = <Reserved<T>>::get(who) - shares;
+		if reserved == 0 {
+			<Reserved<T>>::remove(who);
+			let _ = frame_system::Module::<T>::dec_providers(who);
+		} else {
+			<Reserved<T>>::insert(who, reserved);
+		}
+		TotalReserved::mutate(|total| *total = total.saturating_sub(shares));
+	}
+
+	//Destroys value of the tokens held by 'who'
+	fn do_burn(who: &T::AccountId, value: u64) -> DispatchResult {
+		ensure!(!Self::get_paused(), MyError::<T>::TokenPaused);
+		ensure!(!Self::is_frozen(who), MyError::<T>::AccountFrozen);
+		ensure!(!Self::is_blacklisted(who), MyError::<T>::SenderBlacklisted);
+		ensure!(value > 0, MyError::<T>::ZeroAmount);
+		let remaining = Self::free_balance(who).checked_sub(value).ok_or(MyError::<T>::NotEnoughFunds)?;
+		ensure!(remaining >= Self::locked_balance(who), MyError::<T>::LiquidityRestrictions);
+		let shares = Self::shares_for_debit(who, value);
+
+		Self::try_mutate_balance(who, |balance| -> DispatchResult {
+			*balance = balance.checked_sub(shares).ok_or(MyError::<T>::NotEnoughFunds)?;
+			Ok(())
+		})?;
+		TotalIssuance::mutate(|issuance| *issuance = issuance.saturating_sub(shares));
+
+		Self::deposit_event(RawEvent::Burned(who.clone(), value));
+		Self::sweep_dust(who)
+	}
+
+	//Moves value from 'from' to 'to' on behalf of 'spender', consuming the allowance 'from'
+	//granted to it. An owner spending its own funds needs no allowance and this behaves
+	//like a plain transfer, leaving any self-allowance untouched.
+	fn do_transfer_from(spender: &T::AccountId, from: &T::AccountId, to: &T::AccountId, value: u64) -> DispatchResult {
+		if spender == from {
+			return Self::do_transfer(from, to, value, false);
+		}
+
+		let remaining = Self::spend_allowance(from, spender, Some(to), value, || Self::do_transfer_by(spender, from, to, value, false))?;
+
+		Self::deposit_event(RawEvent::TransferFrom(from.clone(), spender.clone(), to.clone(), value, remaining));
+		Ok(())
+	}
+
+	//Checks that 'spender' may use value of the allowance from 'owner' towards 'dest' (None
+	//when the tokens do not go to anyone), runs 'action' and only then records the spend.
+	//Returns the allowance left afterwards.
+	fn spend_allowance(
+		owner: &T::AccountId,
+		spender: &T::AccountId,
+		dest: Option<&T::AccountId>,
+		value: u64,
+		action: impl FnOnce() -> DispatchResult,
+	) -> Result<u64, DispatchError> {
+		ensure!(!Self::is_blacklisted(spender), MyError::<T>::SpenderBlacklisted);
+		let now = frame_system::Module::<T>::block_number();
+		let mut allowance = Self::allowances(owner, spender).ok_or(MyError::<T>::NotEnoughAllowance)?;
+		ensure!(now >= allowance.active_from, MyError::<T>::ApprovalNotActive);
+		if let Some(expires_at) = allowance.expires_at {
+			ensure!(now <= expires_at, MyError::<T>::AllowanceExpired);
+		}
+		if let Some(allowed_dest) = allowance.allowed_dest.as_ref() {
+			ensure!(Some(allowed_dest) == dest, MyError::<T>::DestinationNotAllowed);
+		}
+		let infinite = allowance.amount == INFINITE_ALLOWANCE;
+		if !infinite {
+			allowance.amount = allowance.amount.checked_sub(value).ok_or(MyError::<T>::NotEnoughAllowance)?;
+		}
+		if let Some(rate_limit) = allowance.rate_limit.as_mut() {
+			ensure!(rate_limit.try_spend(now, value), MyError::<T>::RateLimited);
+		}
+		let remaining = allowance.amount;
+		let exhausted = match allowance.uses_left.as_mut() {
+			Some(uses_left) => {
+				*uses_left = uses_left.saturating_sub(1);
+				*uses_left == 0
+			}
+			None => false,
+		};
+
+		action()?;
+		if exhausted {
+			<Allowances<T>>::remove(owner, spender);
+			Self::deposit_event(RawEvent::ApprovalExhausted(owner.clone(), spender.clone()));
+		} else if !infinite || allowance.uses_left.is_some() || allowance.rate_limit.is_some() {
+			Self::write_allowance(owner, spender, allowance);
+		}
+		Ok(remaining)
+	}
+
+	//Tokens shown for 'shares' at the current scaling factor, rounded down
+	pub fn to_display(shares: u64) -> u64 {
+		Self::shares_to_display(shares, Self::scaling_factor())
+	}
+
+	fn shares_to_display(shares: u64, factor: u128) -> u64 {
+		(shares as u128).saturating_mul(factor).checked_div(SCALING_PRECISION).unwrap_or(0).saturated_into()
+	}
+
+	//Shares making up 'amount' tokens at the current scaling factor, rounded down
+	pub fn to_shares(amount: u64) -> u64 {
+		(amount as u128).saturating_mul(SCALING_PRECISION).checked_div(Self::scaling_factor()).unwrap_or(0).saturated_into()
+	}
+
+	//Shares leaving 'who' when it gives up 'amount' tokens. Giving up its whole balance takes
+	//every share, so rounding leaves no dust behind.
+	fn shares_for_debit(who: &T::AccountId, amount: u64) -> u64 {
+		let shares = <Balances<T>>::get(who);
+		if amount == Self::to_display(shares) {
+			shares
+		} else {
+			Self::to_shares(amount)
+		}
+	}
+
+	//Free balance of 'who' in tokens
+	pub fn free_balance(who: &T::AccountId) -> u64 {
+		Self::to_display(<Balances<T>>::get(who))
+	}
+
+	//Reserved balance of 'who' in tokens
+	pub fn reserved_balance(who: &T::AccountId) -> u64 {
+		Self::to_display(Self::reserved_shares(who))
+	}
+
+	//Number of tokens currently in existence
+	pub fn get_total_issuance() -> u64 {
+		Self::to_display(Self::get_total_shares())
+	}
+
+	//Number of tokens currently reserved
+	pub fn get_total_reserved() -> u64 {
+		Self::to_display(Self::get_total_reserved_shares())
+	}
+
+	//Applies 'f' to the shares of 'who'. Nothing is written if 'f' fails, and the entry
+	//is dropped once the shares reach zero. A live entry holds a provider reference so
+	//frame_system does not reap an account that only holds this token.
+	fn try_mutate_balance<R>(
+		who: &T::AccountId,
+		f: impl FnOnce(&mut u64) -> Result<R, DispatchError>,
+	) -> Result<R, DispatchError> {
+		Self::snapshot_balance(who);
+		let (result, old, new) = <Balances<T>>::try_mutate_exists(who, |maybe_balance| -> Result<_, DispatchError> {
+			let old = maybe_balance.unwrap_or(0);
+			let mut balance = old;
+			let result = f(&mut balance)?;
+			*maybe_balance = if balance == 0 { None } else { Some(balance) };
+			Ok((result, old, balance))
+		})?;
+		let (created, removed) = (old == 0 && new > 0, old > 0 && new == 0);
+		if old != new {
+			let delegate = Self::delegate_of(who);
+			if new > old {
+				Self::move_votes(None, Some(&delegate), new - old);
+			} else {
+				Self::move_votes(Some(&delegate), None, old - new);
+			}
+			Self::correct_dividends(who, old, new);
+		}
+
+		if created {
+			frame_system::Module::<T>::inc_providers(who);
+		}
+		if removed {
+			//Only fails when consumers still depend on this account, which then simply stays alive
+			let _ = frame_system::Module::<T>::dec_providers(who);
+		}
+		Ok(result)
+	}
+
+	//Account that votes with the balance of 'who'; holders vote for themselves by default
+	pub fn delegate_of(who: &T::AccountId) -> T::AccountId {
+		Self::delegates(who).unwrap_or_else(|| who.clone())
+	}
+
+	//Moves 'amount' of voting power from one delegate to another; None on either side stands
+	//for tokens being created or destroyed
+	fn move_votes(from: Option<&T::AccountId>, to: Option<&T::AccountId>, amount: u64) {
+		if amount == 0 || from == to {
+			return;
+		}
+		if let Some(from) = from {
+			let power = Self::vote_shares(from).saturating_sub(amount);
+			Self::write_checkpoint(from, power);
+		}
+		if let Some(to) = to {
+			let power = Self::vote_shares(to).saturating_add(amount);
+			Self::write_checkpoint(to, power);
+		}
+	}
+
+	//Records the voting power of 'delegate' as of the current block, dropping the oldest
+	//checkpoint once MaxCheckpoints are kept
+	fn write_checkpoint(delegate: &T::AccountId, power: u64) {
+		let now = frame_system::Module::<T>::block_number();
+		let old = Self::vote_shares(delegate);
+		<Checkpoints<T>>::mutate(delegate, |checkpoints| {
+			match checkpoints.last_mut() {
+				Some(last) if last.0 == now => last.1 = power,
+				_ => checkpoints.push((now, power)),
+			}
+			if checkpoints.len() > T::MaxCheckpoints::get() as usize {
+				checkpoints.remove(0);
+			}
+		});
+		Self::deposit_event(RawEvent::DelegateVotesChanged(delegate.clone(), Self::to_display(old), Self::to_display(power)));
+	}
+
+	//Shares 'who' currently votes with; checkpoints count shares so rebases carry over
+	fn vote_shares(who: &T::AccountId) -> u64 {
+		Self::checkpoints(who).last().map_or(0, |(_, power)| *power)
+	}
+
+	//Current voting power of 'who'
+	pub fn get_votes(who: &T::AccountId) -> u64 {
+		Self::to_display(Self::vote_shares(who))
+	}
+
+	//Voting power 'who' had at the end of 'block', in tokens at the current scaling factor.
+	//Blocks older than the oldest kept checkpoint report 0.
+	pub fn get_past_votes(who: &T::AccountId, block: T::BlockNumber) -> u64 {
+		let shares = Self::checkpoints(who).iter().rev().find(|(at, _)| *at <= block).map_or(0, |(_, power)| *power);
+		Self::to_display(shares)
+	}
+
+	//Keeps the balance 'who' had when the latest snapshot was taken, the first time it is about
+	//to change afterwards
+	fn snapshot_balance(who: &T::AccountId) {
+		let current = Self::current_snapshot();
+		if current > 0 && Self::last_snapshotted(who) < current {
+			<SnapshotBalances<T>>::insert(current, who, <Balances<T>>::get(who));
+			<LastSnapshotted<T>>::insert(who, current);
+		}
+	}
+
+	//Balance 'who' held when snapshot 'snapshot_id' was taken. The balance is only copied
+	//when it first changes after a snapshot, so the first copy made at or after
+	//'snapshot_id' holds it; without one the balance has not changed since. Copies are kept
+	//in shares and shown at the scaling factor of the snapshot.
+	pub fn balance_at_snapshot(snapshot_id: u32, who: &T::AccountId) -> u64 {
+		let shares = (snapshot_id..=Self::last_snapshotted(who))
+			.find_map(|id| Self::snapshot_balances(id, who))
+			.unwrap_or_else(|| <Balances<T>>::get(who));
+		Self::shares_to_display(shares, Self::snapshot_factors(snapshot_id).unwrap_or_else(Self::scaling_factor))
+	}
+
+	//Removes up to 'limit' allowances granted by 'owner', returning how many were removed
+	fn clear_allowances_of(owner: &T::AccountId, limit: u32) -> u32 {
+		<Allowances<T>>::drain_prefix(owner).take(limit as usize).count() as u32
+	}
+
+	//Amount 'spender' may still withdraw from 'owner', ignoring any other terms
+	fn allowance_amount(owner: &T::AccountId, spender: &T::AccountId) -> u64 {
+		Self::allowances(owner, spender).map_or(0, |allowance| allowance.amount)
+	}
+
+	//Stores a granted allowance and announces it. New or increased allowances only become
+	//spendable after ApprovalDelay and are refused for blacklisted spenders; decreases keep the
+	//activation block already in place.
+	fn set_allowance(owner: T::AccountId, spender: T::AccountId, mut allowance: AllowanceOf<T>) -> DispatchResult {
+		allowance.active_from = match Self::allowances(&owner, &spender) {
+			Some(current) if allowance.amount <= current.amount => current.active_from,
+			_ => {
+				//Blacklisted spenders can still have their allowances lowered or removed
+				ensure!(!Self::is_blacklisted(&spender), MyError::<T>::SpenderBlacklisted);
+				frame_system::Module::<T>::block_number().saturating_add(T::ApprovalDelay::get())
+			}
+		};
+		Self::write_allowance(&owner, &spender, allowance.clone());
+		Self::deposit_event(RawEvent::Approval(owner, spender, allowance));
+		Ok(())
+	}
+
+	//Stores an allowance, dropping the entry instead of keeping a zero around
+	fn write_allowance(owner: &T::AccountId, spender: &T::AccountId, allowance: AllowanceOf<T>) {
+		if allowance.amount == 0 {
+			<Allowances<T>>::remove(owner, spender);
+		} else {
+			<Allowances<T>>::insert(owner, spender, allowance);
+		}
+	}
+}
+
+impl<T: Config> TokenLocks<T::AccountId, T::BlockNumber> for Module<T> {
+	fn set_lock(id: LockIdentifier, who: &T::AccountId, amount: u64, until: Option<T::BlockNumber>) -> DispatchResult {
+		Module::<T>::set_lock(id, who, amount, until)
+	}
+
+	fn extend_lock(id: LockIdentifier, who: &T::AccountId, amount: u64, until: Option<T::BlockNumber>) -> DispatchResult {
+		let now = frame_system::Module::<T>::block_number();
+		let (amount, until) = match Self::locks(who).into_iter().find(|lock| lock.id == id && lock.is_active(&now)) {
+			Some(lock) => {
+				let until = match (lock.until, until) {
+					(Some(current), Some(requested)) => Some(current.max(requested)),
+					_ => None,
+				};
+				(lock.amount.max(amount), until)
+			}
+			None => (amount, until),
+		};
+		Module::<T>::set_lock(id, who, amount, until)
+	}
+
+	fn remove_lock(id: LockIdentifier, who: &T::AccountId) {
+		Module::<T>::remove_lock(id, who)
+	}
+
+	fn locked_balance(who: &T::AccountId) -> u64 {
+		Module::<T>::locked_balance(who)
+	}
+}
+
+//Removes the token state of accounts reaped by frame_system. Wire it up in the
+//runtime through `type OnKilledAccount = Erc20;` in `frame_system::Config`.
+impl<T: Config> OnKilledAccount<T::AccountId> for Module<T> {
+	//Balances and Reserved entries each hold a provider reference, so this only finds tokens
+	//left on accounts whose references were never taken. The entries are taken directly, as
+	//try_mutate_balance would give up a reference the account no longer has, but the votes,
+	//snapshot and dividends of both sides are kept up to date the way it would.
+	fn on_killed_account(who: &T::AccountId) {
+		Self::snapshot_balance(who);
+		let free = <Balances<T>>::take(who);
+		let reserved = <Reserved<T>>::take(who);
+		TotalReserved::mutate(|total| *total = total.saturating_sub(reserved));
+		Self::move_votes(Some(&Self::delegate_of(who)), None, free);
+
+		let shares = free.saturating_add(reserved);
+		if shares > 0 {
+			let amount = Self::to_display(shares);
+			let swept = match T::DormantDestination::get() {
+				Some(dest) if &dest != who => Self::try_mutate_balance(&dest, |balance| -> DispatchResult {
+					*balance = balance.checked_add(shares).ok_or(MyError::<T>::Overflow)?;
+					Ok(())
+				}).is_ok(),
+				_ => false,
+			};
+			if swept {
+				Self::deposit_event(RawEvent::DormantSwept(who.clone(), amount));
+			} else {
+				TotalIssuance::mutate(|issuance| *issuance = issuance.saturating_sub(shares));
+				Self::deposit_event(RawEvent::Burned(who.clone(), amount));
+			}
+		}
+
+		//Dividends accrued but not claimed by a reaped account are forfeited
+		<DividendCorrections<T>>::remove(who);
+		<WithdrawnDividends<T>>::remove(who);
+		Self::clear_allowances_of(who, T::MaxAllowanceRemovals::get());
 	}
 }
 
 decl_storage! {
 	trait Store for Module<T: Config> as TokenStorage {
+		//Cap on the number of tokens that may ever be in existence
 		pub MaxSupply get(fn get_max_supply): u64;
+		//Number of shares currently in existence; get_total_issuance gives them in tokens
+		pub TotalIssuance get(fn get_total_shares): u64;
+		//Tokens shown per share, scaled by SCALING_PRECISION
+		pub ScalingFactor get(fn scaling_factor): u128 = SCALING_PRECISION;
 		pub Decimals get(fn get_decimals): u8 = 18;
+		pub MetadataFrozen get(fn get_metadata_frozen): bool;
+		pub Paused get(fn get_paused): bool;
+		//Set while root loads balances with force_import_balances; transfers are blocked meanwhile
+		pub ImportOpen get(fn import_open): bool;
+		//Tokens loaded by the current or last balance import
+		pub ImportedTotal get(fn imported_total): u64;
+		//Whether the current pause was caused by the circuit breaker
+		pub BreakerTripped get(fn get_breaker_tripped): bool;
+		pub MaxVolumePerBlock get(fn max_volume_per_block): Option<u64>;
+		//Value moved by transfers so far in the current block
+		pub BlockVolume get(fn block_volume): u64;
+		pub Halted get(fn get_halted): bool;
+		pub Soulbound get(fn get_soulbound): bool;
+		pub SoulboundExempt get(fn soulbound_exempt): map hasher(blake2_128_concat) T::AccountId => bool;
+		pub TransferPolicyOf get(fn transfer_policy): TransferPolicy;
+		pub Whitelisted get(fn whitelisted): map hasher(blake2_128_concat) T::AccountId => bool;
+		pub Blacklist get(fn blacklist): map hasher(blake2_128_concat) T::AccountId => bool;
+		//Frozen accounts, mapped to whether incoming transfers are blocked as well
+		pub FrozenAccounts get(fn frozen_accounts): map hasher(blake2_128_concat) T::AccountId => Option<bool>;
+		pub TokenUri get(fn get_token_uri): Vec<u8>;
+		pub LogoHash get(fn get_logo_hash): Option<[u8; 32]>;
 		pub Ticker get(fn get_ticker): Vec::<u8>;
 		pub Minted get(fn get_mint): bool = false;
 		pub Name get(fn get_name): Vec::<u8>;
+		//Account allowed to administer the token
+		pub Owner get(fn get_owner): Option<T::AccountId>;
+		pub PendingOwner get(fn get_pending_owner): Option<T::AccountId>;
+		//Bit set of Role::bit values held by each account
+		pub Roles get(fn get_roles): map hasher(blake2_128_concat) T::AccountId => u8;
+		//Per-block issuance, if any
+		pub Inflation get(fn inflation): Option<InflationConfigOf<T>>;
+		pub MemoRequired get(fn memo_required): map hasher(blake2_128_concat) T::AccountId => bool;
+		//Block at which each (sender, reference) of transfer_idempotent was last used
+		pub UsedReferences get(fn used_references): double_map hasher(blake2_128_concat) T::AccountId, hasher(blake2_128_concat) [u8; 32] => Option<T::BlockNumber>;
+		//References to forget once the given block starts
+		pub ReferenceExpiries get(fn reference_expiries): map hasher(twox_64_concat) T::BlockNumber => Vec<(T::AccountId, [u8; 32])>;
+		//Accounts allowed to issue tokens within a quota
+		pub Minters get(fn minters): map hasher(blake2_128_concat) T::AccountId => Option<MinterQuota<T::BlockNumber>>;
+		//Smallest balance an account entry may hold
+		pub MinBalance get(fn get_min_balance): u64;
+		pub DustPolicyOf get(fn dust_policy): DustPolicy;
+		//Accounts pre-created with touch, with who paid their deposit and how much
+		pub Touched get(fn touched): map hasher(blake2_128_concat) T::AccountId => Option<(T::AccountId, BalanceOf<T>)>;
+		//Shares held by each account; free_balance gives them in tokens
 		pub Balances get(fn balances): map hasher(blake2_128_concat) T::AccountId => u64;
-		pub Allowances get(fn allowances): double_map hasher(blake2_128_concat) T::AccountId, hasher(blake2_128_concat) T::AccountId => u64;
+		//Keyed by (owner, spender). `approve` has always written entries in this order; earlier
+		//`transfer_from` looked them up as (owner, recipient), so existing entries need no migration.
+		pub Allowances get(fn allowances): double_map hasher(blake2_128_concat) T::AccountId, hasher(blake2_128_concat) T::AccountId => Option<AllowanceOf<T>>;
+		//Raw key of the last Balances entry visited by the audit in progress
+		pub AuditCursor get(fn audit_cursor): Option<Vec<u8>>;
+		//Running total and holder count of the audit in progress
+		pub AuditProgress get(fn audit_progress): (u128, u32);
+		pub TransferFeeConfig get(fn transfer_fee): Option<TransferFee>;
+		//Basis points of every transfer that are burned
+		pub BurnRateBps get(fn burn_rate_bps): u16;
+		//Accounts transfers to or from which skip the fee and burn
+		pub DeductionExempt get(fn deduction_exempt): map hasher(blake2_128_concat) T::AccountId => bool;
+		pub MaxTransferAmount get(fn max_transfer_amount): Option<u64>;
+		pub MinTransferAmount get(fn min_transfer_amount): u64;
+		//Tokens held apart from the free balance; they count towards TotalIssuance but cannot move
+		pub Reserved get(fn reserved_shares): map hasher(blake2_128_concat) T::AccountId => u64;
+		pub TotalReserved get(fn get_total_reserved_shares): u64;
+		pub Vesting get(fn vesting): map hasher(blake2_128_concat) T::AccountId => Vec<VestingSchedule<T::BlockNumber>>;
+		//Pending transfer_locked amounts with the block each unlocks at
+		pub TimeLocks get(fn time_locks): map hasher(blake2_128_concat) T::AccountId => Vec<(u64, T::BlockNumber)>;
+		pub Locks get(fn locks): map hasher(blake2_128_concat) T::AccountId => Vec<BalanceLock<T::BlockNumber>>;
+		pub TransferLimits get(fn transfer_limits): map hasher(blake2_128_concat) T::AccountId => Option<RateLimit<T::BlockNumber>>;
+		//Loosened limit (max per period, zero to remove, and period) with the block it applies from
+		pub PendingTransferLimit get(fn pending_transfer_limit): map hasher(blake2_128_concat) T::AccountId => Option<(u64, T::BlockNumber, T::BlockNumber)>;
+		pub Scheduled get(fn scheduled): map hasher(twox_64_concat) u64 => Option<ScheduledTransferOf<T>>;
+		//Ids of the scheduled transfers to run at each block
+		pub ScheduleQueue get(fn schedule_queue): map hasher(twox_64_concat) T::BlockNumber => Vec<u64>;
+		pub NextScheduleId get(fn next_schedule_id): u64;
+		pub Subscriptions get(fn subscriptions): double_map hasher(blake2_128_concat) T::AccountId, hasher(twox_64_concat) u64 => Option<SubscriptionOf<T>>;
+		pub NextSubscriptionId get(fn next_subscription_id): u64;
+		pub Streams get(fn streams): map hasher(twox_64_concat) u64 => Option<StreamOf<T>>;
+		pub NextStreamId get(fn next_stream_id): u64;
+		pub Invoices get(fn invoices): map hasher(twox_64_concat) u64 => Option<InvoiceOf<T>>;
+		pub NextInvoiceId get(fn next_invoice_id): u64;
+		//Accounts voting with someone else's balance, mapped to that delegate
+		pub Delegates get(fn delegates): map hasher(blake2_128_concat) T::AccountId => Option<T::AccountId>;
+		//Voting power of each delegate over time, as (block, power) in block order
+		pub Checkpoints get(fn checkpoints): map hasher(blake2_128_concat) T::AccountId => Vec<(T::BlockNumber, u64)>;
+		//Latest snapshot id; 0 until the first snapshot
+		pub CurrentSnapshot get(fn current_snapshot): u32;
+		pub SnapshotBlocks get(fn snapshot_blocks): map hasher(twox_64_concat) u32 => Option<T::BlockNumber>;
+		//Tokens each Ethereum address can claim with claim_eth
+		pub EthClaims get(fn eth_claims): map hasher(identity) EthereumAddress => Option<u64>;
+		pub ClaimDrops get(fn claim_drops): map hasher(blake2_128_concat) u64 => Option<ClaimDropOf<T>>;
+		pub NextClaimDropId get(fn next_claim_drop_id): u64;
+		pub Claimed get(fn claimed): double_map hasher(blake2_128_concat) u64, hasher(blake2_128_concat) T::AccountId => bool;
+		pub Faucet get(fn faucet_config): Option<FaucetConfigOf<T>>;
+		//Block each account last received a faucet drip at
+		pub LastDrip get(fn last_drip): map hasher(blake2_128_concat) T::AccountId => Option<T::BlockNumber>;
+		pub ConvictionLocks get(fn conviction_locks): map hasher(blake2_128_concat) T::AccountId => Option<ConvictionLock<T::BlockNumber>>;
+		//Scaling factor each snapshot was taken at
+		pub SnapshotFactors get(fn snapshot_factors): map hasher(twox_64_concat) u32 => Option<u128>;
+		pub SnapshotBalances get(fn snapshot_balances): double_map hasher(twox_64_concat) u32, hasher(blake2_128_concat) T::AccountId => Option<u64>;
+		//Latest snapshot each account's balance was copied for
+		pub LastSnapshotted get(fn last_snapshotted): map hasher(blake2_128_concat) T::AccountId => u32;
+		pub Stakes get(fn stakes): map hasher(blake2_128_concat) T::AccountId => StakeInfo;
+		pub TotalStaked get(fn total_staked): u64;
+		pub StakingRewardPerBlock get(fn staking_reward_per_block): u64;
+		//Rewards issued per staked token so far, scaled by REWARD_PRECISION
+		pub RewardPerShare get(fn reward_per_share): u128;
+		pub LastRewardBlock get(fn last_reward_block): T::BlockNumber;
+		//Unstaked tokens waiting to be withdrawn, with the block they can be withdrawn from
+		pub Unbonding get(fn unbonding): map hasher(blake2_128_concat) T::AccountId => Option<(u64, T::BlockNumber)>;
+		pub Swaps get(fn swaps): map hasher(blake2_128_concat) u64 => Option<SwapOf<T>>;
+		pub NextSwapId get(fn next_swap_id): u64;
+		//Collateral required by new positions, in basis points of the tokens minted
+		pub CollateralRatioBps get(fn collateral_ratio_bps): u32;
+		pub CollateralPositions get(fn collateral_positions): map hasher(blake2_128_concat) T::AccountId => Option<CollateralPositionOf<T>>;
+		//Tokens issued by wrap and not unwrapped yet, each backed by native currency in the wrap account
+		pub WrappedSupply get(fn wrapped_supply): u64;
+		pub CurrentSale get(fn current_sale): Option<SaleOf<T>>;
+		//Number of sales started so far; the current sale's contributions are kept under it
+		pub SaleIndex get(fn sale_index): u32;
+		//Native currency each account paid into the sale with the given index, and the tokens it bought
+		pub Contributions get(fn contributions): double_map hasher(twox_64_concat) u32, hasher(blake2_128_concat) T::AccountId => (BalanceOf<T>, u64);
+		//Dividends funded but not claimed yet, held in the pallet account
+		pub DividendPot get(fn dividend_pot): u64;
+		//Dividends funded per token held so far, scaled by DIVIDEND_MAGNITUDE
+		pub MagnifiedDividendPerShare get(fn magnified_dividend_per_share): u128;
+		//Offsets MagnifiedDividendPerShare * balance for the balance changes since each funding
+		pub DividendCorrections get(fn dividend_corrections): map hasher(blake2_128_concat) T::AccountId => i128;
+		pub WithdrawnDividends get(fn withdrawn_dividends): map hasher(blake2_128_concat) T::AccountId => u64;
+		pub Escrows get(fn escrows): map hasher(twox_64_concat) u64 => Option<EscrowOf<T>>;
+		pub NextEscrowId get(fn next_escrow_id): u64;
+		//Part of the pallet account's balance that is owed to users
+		pub Earmarked get(fn get_earmarked): u64;
+		//Id the next Transfer event will carry; ids increase by one per transfer
+		pub TransferCounter get(fn transfer_counter): u64;
+		pub StorageVersion get(fn storage_version) build(|_| Releases::V7): Releases;
 	}
 }
 
@@ -168,6 +4505,134 @@ decl_error! {
 		NotEnoughFunds,
 		AlreadyMinted,
 		NotEnoughAllowance,
+		Overflow,
+		ZeroAmount,
+		SelfTransfer,
+		CapExceeded,
+		ZeroSupply,
+		InvalidTicker,
+		TooManyDecimals,
+		EmptyName,
+		AllowanceMismatch,
+		AllowanceExpired,
+		DestinationNotAllowed,
+		RateLimited,
+		ApprovalNotActive,
+		NotOwner,
+		NoPendingOwner,
+		NotPendingOwner,
+		NoOwner,
+		RenounceNotConfirmed,
+		MissingRole,
+		RoleNotHeld,
+		DecimalsLocked,
+		MetadataIsFrozen,
+		UriTooLong,
+		TokenPaused,
+		NotPaused,
+		CircuitBreaker,
+		Halted,
+		NotHalted,
+		AccountFrozen,
+		NotFrozen,
+		SenderBlacklisted,
+		RecipientBlacklisted,
+		SpenderBlacklisted,
+		AlreadyBlacklisted,
+		NotBlacklisted,
+		NotWhitelisted,
+		NonTransferable,
+		NothingToRecover,
+		TransferLimitExceeded,
+		AboveMaxTransfer,
+		BelowMinTransfer,
+		FeeTooHigh,
+		NotExempt,
+		LiquidityRestrictions,
+		TooManyLocks,
+		TooManyVestingSchedules,
+		AmountLow,
+		NotVesting,
+		InvalidUnlockBlock,
+		UnknownEscrow,
+		NotEscrowBeneficiary,
+		NotEscrowCreator,
+		EscrowExpired,
+		EscrowNotExpired,
+		TooManyScheduled,
+		UnknownScheduled,
+		NotScheduler,
+		ReservationSlashed,
+		UnknownSubscription,
+		NotMerchant,
+		NotSubscriptionParty,
+		PeriodNotElapsed,
+		InvalidStreamPeriod,
+		UnknownStream,
+		NotStreamRecipient,
+		NotStreamParty,
+		NothingToWithdraw,
+		UnknownInvoice,
+		NotInvoicePayer,
+		NotInvoiceCreator,
+		InvoiceExpired,
+		AlreadyDelegated,
+		NotEnoughStaked,
+		StillUnbonding,
+		NoDividendHolders,
+		InvalidSale,
+		SaleActive,
+		NoSale,
+		SaleNotOpen,
+		HardCapExceeded,
+		SaleFinalized,
+		SaleNotEnded,
+		SoftCapReached,
+		NoContribution,
+		InsufficientBacking,
+		InvalidCollateralRatio,
+		NoPosition,
+		ExceedsPosition,
+		UnknownSwap,
+		NotSwapCounterparty,
+		NotSwapCreator,
+		SwapExpired,
+		InvalidRebase,
+		AirdropTooLarge,
+		DistributionMismatch,
+		BelowMinimum,
+		MinBalanceRaised,
+		AlreadyTouched,
+		NotTouched,
+		NotTouchParty,
+		AccountNotEmpty,
+		InvalidConviction,
+		ConvictionLocked,
+		NotConvictionLocked,
+		ConvictionNotExpired,
+		ImportIsOpen,
+		ImportNotOpen,
+		DuplicateImport,
+		InvalidEthSignature,
+		NoEthClaim,
+		UnknownDrop,
+		NotDropCreator,
+		DropExpired,
+		DropNotExpired,
+		AlreadyClaimed,
+		InvalidProof,
+		FaucetDisabled,
+		FaucetCooldown,
+		NotMinter,
+		MinterExpired,
+		QuotaExceeded,
+		WouldKill,
+		BatchTooLarge,
+		EmptyBatch,
+		MemoTooLong,
+		DuplicateReference,
+		TooManyReferences,
+		MemoRequired,
 	}
 }
 
@@ -175,15 +4640,244 @@ decl_event! (
 	pub enum Event<T>
 	where
 		AccountId = <T as frame_system::Config>::AccountId,
+		BlockNumber = <T as frame_system::Config>::BlockNumber,
+		Allowance = AllowanceOf<T>,
+		Inflation = InflationConfigOf<T>,
+		Vesting = VestingSchedule<<T as frame_system::Config>::BlockNumber>,
+		Subscription = SubscriptionOf<T>,
+		Stream = StreamOf<T>,
+		Invoice = InvoiceOf<T>,
+		Balance = BalanceOf<T>,
+		Sale = SaleOf<T>,
+		Swap = SwapOf<T>,
+		Faucet = FaucetConfigOf<T>,
+		Conviction = ConvictionLock<<T as frame_system::Config>::BlockNumber>,
 	{
-		NameReturned(Vec::<u8>),
-		TickerReturned(Vec::<u8>),
-		DecimalsReturned(u8),
-		Minted(bool),
-		TotalSupplyReturned(u64),
-		BalanceReturned(u64),
-		Transfer(AccountId, AccountId, u64),
-		Approval(AccountId, AccountId, u64),
-		AllowanceReturned(u64),
+		//Query events carry the account that asked first, followed by the value read
+		NameReturned(AccountId, Vec::<u8>),
+		TickerReturned(AccountId, Vec::<u8>),
+		DecimalsReturned(AccountId, u8),
+		//Name, ticker, decimals, token URI, logo hash, metadata frozen
+		TokenInfoReturned(AccountId, Vec::<u8>, Vec::<u8>, u8, Vec::<u8>, Option<[u8; 32]>, bool),
+		//Account that administers the token from now on
+		OwnerSet(AccountId),
+		//Owner, nominee
+		OwnershipTransferStarted(AccountId, AccountId),
+		//Previous owner, new owner
+		OwnershipTransferred(AccountId, AccountId),
+		//Owner, nominee that was withdrawn
+		OwnershipTransferCancelled(AccountId, AccountId),
+		//Last owner; the token has no administrator from now on
+		OwnershipRenounced(AccountId),
+		//Account that paused the token
+		Paused(AccountId),
+		//Account that unpaused the token, None for root
+		Unpaused(Option<AccountId>),
+		//Block, volume the refused transfer would have reached
+		CircuitBreakerTripped(BlockNumber, u64),
+		MaxVolumePerBlockSet(Option<u64>),
+		EmergencyHalted,
+		EmergencyResumed,
+		//Account, whether incoming transfers are blocked too
+		AccountFrozen(AccountId, bool),
+		AccountThawed(AccountId),
+		Blacklisted(AccountId),
+		Unblacklisted(AccountId),
+		SoulboundSet(bool),
+		SoulboundExemptSet(AccountId, bool),
+		TransferPolicySet(TransferPolicy),
+		WhitelistAdded(AccountId),
+		WhitelistRemoved(AccountId),
+		RoleGranted(AccountId, Role),
+		RoleRevoked(AccountId, Role),
+		//Old name, ticker and decimals, then the new ones
+		MetadataUpdated(Vec::<u8>, Vec::<u8>, u8, Vec::<u8>, Vec::<u8>, u8),
+		MetadataFrozen,
+		//New URI and logo hash, empty and None when cleared
+		TokenUriUpdated(Vec::<u8>, Option<[u8; 32]>),
+		//Creator, ticker, cap
+		Minted(AccountId, Vec::<u8>, u64),
+		//Beneficiary, amount of newly issued tokens
+		Issued(AccountId, u64),
+		//Minter, quota, last block it is valid
+		MinterSet(AccountId, u64, BlockNumber),
+		//Minter, amount issued, quota remaining
+		MinterQuotaUsed(AccountId, u64, u64),
+		MinterRevoked(AccountId),
+		//New per-block inflation, None when switched off
+		InflationSet(Option<Inflation>),
+		TotalSupplyReturned(AccountId, u64),
+		MaxSupplyReturned(AccountId, u64),
+		//Account whose balance was read, balance
+		BalanceReturned(AccountId, u64),
+		//Account, free balance, reserved balance
+		ReservedBalanceReturned(AccountId, u64, u64),
+		//Transfer id, sender, recipient, value. The leading id was added after the initial
+		//release, so decoders of the older three-field event need updating.
+		Transfer(u64, AccountId, AccountId, u64),
+		//Sender, recipient, value, memo
+		TransferWithMemo(AccountId, AccountId, u64, Vec::<u8>),
+		//From, to, value moved by root
+		ForceTransferred(AccountId, AccountId, u64),
+		MemoRequirementSet(AccountId, bool),
+		//Owner, spender, recipient, value, allowance remaining afterwards
+		TransferFrom(AccountId, AccountId, AccountId, u64, u64),
+		//Owner, spender, allowance as now stored
+		Approval(AccountId, AccountId, Allowance),
+		//Owner, spender
+		ApprovalRevoked(AccountId, AccountId),
+		//Owner, spender; the allowance ran out of uses and was removed
+		ApprovalExhausted(AccountId, AccountId),
+		//Owner, number of allowances removed
+		AllowancesCleared(AccountId, u32),
+		//Owner, spender, allowance
+		AllowanceReturned(AccountId, AccountId, Allowance),
+		//Reaped account, amount swept to the DormantDestination
+		DormantSwept(AccountId, u64),
+		//Account, amount of tokens destroyed
+		Burned(AccountId, u64),
+		//Touched account, depositor, deposit reserved
+		Touched(AccountId, AccountId, Balance),
+		//Touched account, depositor, deposit returned
+		TouchRefunded(AccountId, AccountId, Balance),
+		MinBalanceSet(u64),
+		DustPolicySet(DustPolicy),
+		//Account swept for holding less than MinBalance, tokens swept
+		DustLost(AccountId, u64),
+		//Owner, recipient, tokens spent from the treasury
+		TreasurySpent(AccountId, AccountId, u64),
+		//Account that asked, treasury account, its balance
+		TreasuryBalanceReturned(AccountId, AccountId, u64),
+		//Sender, collector, fee taken on top of the Transfer of the rest
+		FeeCharged(AccountId, AccountId, u64),
+		//New transfer fee, None when removed
+		TransferFeeSet(Option<TransferFee>),
+		BurnRateSet(u16),
+		ExemptAdded(AccountId),
+		ExemptRemoved(AccountId),
+		//Account, lock id, amount locked, block the lock lifts at
+		LockSet(AccountId, LockIdentifier, u64, Option<BlockNumber>),
+		LockRemoved(AccountId, LockIdentifier),
+		//Beneficiary, schedule added
+		VestingCreated(AccountId, Vesting),
+		//Account whose schedules have all vested
+		VestingCompleted(AccountId),
+		StreamCreated(u64, Stream),
+		InvoiceCreated(u64, Invoice),
+		//Invoice id, payer, creator, amount
+		InvoicePaid(u64, AccountId, AccountId, u64),
+		InvoiceCancelled(u64),
+		StakingRewardSet(u64),
+		Staked(AccountId, u64),
+		Unstaked(AccountId, u64),
+		//Account, unbonded tokens paid out
+		Withdrawn(AccountId, u64),
+		RewardsClaimed(AccountId, u64),
+		SaleStarted(AccountId, Sale),
+		//Buyer, native currency paid, tokens bought
+		TokensPurchased(AccountId, Balance, u64),
+		//Native currency raised, whether the soft cap was met
+		SaleFinalized(Balance, bool),
+		//Contributor, native currency returned, tokens burned
+		Refunded(AccountId, Balance, u64),
+		ConvictionLocked(AccountId, Conviction),
+		//Account, tokens unlocked
+		ConvictionReleased(AccountId, u64),
+		SwapCreated(u64, Swap),
+		//Swap id, creator, counterparty, tokens sold, native currency paid for them
+		SwapExecuted(u64, AccountId, AccountId, u64, Balance),
+		SwapCancelled(u64),
+		CollateralRatioSet(u32),
+		//Account, collateral reserved by the position, tokens minted by it
+		PositionOpened(AccountId, Balance, u64),
+		PositionAdjusted(AccountId, Balance, u64),
+		//Account, collateral released by the final redemption
+		PositionClosed(AccountId, Balance),
+		Wrapped(AccountId, u64),
+		Unwrapped(AccountId, u64),
+		DistributionFunded(AccountId, u64),
+		DividendClaimed(AccountId, u64),
+		//Delegator, previous delegate, new delegate
+		DelegateChanged(AccountId, AccountId, AccountId),
+		//Delegate, previous voting power, new voting power
+		DelegateVotesChanged(AccountId, u64, u64),
+		//Snapshot id, block it was taken at
+		SnapshotTaken(u32, BlockNumber),
+		ImportOpened,
+		//Balances loaded by the chunk, tokens they hold
+		BalancesImported(u32, u64),
+		//Tokens loaded by the whole import
+		ImportClosed(u64),
+		EthClaimSet([u8; 20], Option<u64>),
+		//Claiming account, Ethereum address that signed, tokens issued
+		EthClaimed(AccountId, [u8; 20], u64),
+		//Drop id, creator, merkle root, tokens funded, expiry
+		ClaimDropCreated(u64, AccountId, [u8; 32], u64, BlockNumber),
+		DropClaimed(u64, AccountId, u64),
+		//Drop id, unclaimed tokens returned to the creator
+		ClaimDropSwept(u64, u64),
+		//Drop id, number of claim records removed
+		ClaimRecordsCleared(u64, u32),
+		//Recipients after merging repeats, tokens sent in total
+		AirdropCompleted(u32, u64),
+		FaucetConfigSet(Option<Faucet>),
+		Dripped(AccountId, u64),
+		//Previous scaling factor, new scaling factor, total supply at the new factor
+		Rebased(u128, u128, u64),
+		//Stream id, recipient, amount paid out
+		StreamWithdrawn(u64, AccountId, u64),
+		//Stream id, paid to the recipient, returned to the sender
+		StreamCancelled(u64, u64, u64),
+		//Subscriber, subscription id, terms
+		SubscriptionCreated(AccountId, u64, Subscription),
+		//Subscriber, subscription id, amount collected
+		SubscriptionCollected(AccountId, u64, u64),
+		//Subscriber, subscription id; the last period was collected
+		SubscriptionEnded(AccountId, u64),
+		//Subscriber, subscription id, account that cancelled
+		SubscriptionCancelled(AccountId, u64, AccountId),
+		//Schedule id, sender, recipient, value reserved, block it runs at
+		TransferScheduled(u64, AccountId, AccountId, u64, BlockNumber),
+		ScheduledCancelled(u64),
+		ScheduledExecuted(u64),
+		ScheduledFailed(u64, DispatchError),
+		//Escrow id, creator, beneficiary, value held, deadline
+		EscrowCreated(u64, AccountId, AccountId, u64, BlockNumber),
+		//Escrow id, beneficiary, value paid out
+		EscrowClaimed(u64, AccountId, u64),
+		//Escrow id, creator, value returned
+		EscrowRefunded(u64, AccountId, u64),
+		//Sender, recipient, value, block the received tokens unlock at
+		LockedTransfer(AccountId, AccountId, u64, BlockNumber),
+		Reserved(AccountId, u64),
+		Unreserved(AccountId, u64),
+		//Account, reserved amount destroyed
+		Slashed(AccountId, u64),
+		//Largest single transfer, None when uncapped
+		MaxTransferSet(Option<u64>),
+		//Smallest transfer allowed, zero when disabled
+		MinTransferSet(u64),
+		//Account, max per period, period in blocks
+		TransferLimitSet(AccountId, u64, BlockNumber),
+		//Account, max per period (zero when removed), period, block it takes effect at
+		TransferLimitScheduled(AccountId, u64, BlockNumber, BlockNumber),
+		//Beneficiary, amount taken out of the pallet account
+		StrandedRecovered(AccountId, u64),
+		//Account, balance before and after force_set_balance
+		BalanceSet(AccountId, u64, u64),
+		//Blacklisted account, amount destroyed by root
+		Confiscated(AccountId, u64),
+		//Owner, spender, value burned, allowance remaining afterwards
+		BurnedFrom(AccountId, AccountId, u64, u64),
+		//Index of the skipped item, why it failed
+		BatchItemFailed(u32, DispatchError),
+		//Items that went through, items that were skipped
+		BatchCompleted(u32, u32),
+		//Owner that had nothing sweep_approved could move
+		SweepSkipped(AccountId),
+		//Computed total, recorded TotalIssuance, number of holders visited
+		AuditCompleted(u128, u64, u32),
+		//Computed total, recorded TotalIssuance
+		AuditMismatch(u128, u64),
 	}
 );
@@ -0,0 +1,274 @@
+use crate as simple_erc20;
+use frame_support::{parameter_types, traits::{Get, OnInitialize}};
+use sp_core::H256;
+use sp_runtime::{
+	testing::Header,
+	traits::{BlakeTwo256, LookupError, StaticLookup},
+	ModuleId,
+};
+use std::cell::RefCell;
+
+type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
+type Block = frame_system::mocking::MockBlock<Test>;
+
+frame_support::construct_runtime!(
+	pub enum Test where
+		Block = Block,
+		NodeBlock = Block,
+		UncheckedExtrinsic = UncheckedExtrinsic,
+	{
+		System: frame_system::{Module, Call, Config, Storage, Event<T>},
+		NativeBalances: pallet_balances::{Module, Call, Storage, Config<T>, Event<T>},
+		Erc20: simple_erc20::{Module, Call, Storage, Event<T>},
+		Locker: locker::{Module, Call},
+	}
+);
+
+//Stands in for a governance or staking pallet that only knows this token through TokenLocks
+pub mod locker {
+	use crate::{LockIdentifier, TokenLocks};
+	use frame_support::{decl_module, dispatch::DispatchResult};
+	use frame_system::ensure_signed;
+
+	pub const LOCKER_ID: LockIdentifier = *b"locker  ";
+
+	pub trait Config: frame_system::Config {
+		type Locks: TokenLocks<Self::AccountId, Self::BlockNumber>;
+	}
+
+	decl_module! {
+		pub struct Module<T: Config> for enum Call where origin: T::Origin {
+			#[weight = 0]
+			fn lock(origin, amount: u64, until: Option<T::BlockNumber>) -> DispatchResult {
+				let who = ensure_signed(origin)?;
+				T::Locks::extend_lock(LOCKER_ID, &who, amount, until)
+			}
+
+			#[weight = 0]
+			fn unlock(origin) -> DispatchResult {
+				let who = ensure_signed(origin)?;
+				T::Locks::remove_lock(LOCKER_ID, &who);
+				Ok(())
+			}
+		}
+	}
+}
+
+//Accounts are u128 so the pallet account and its sub-accounts do not collide once truncated
+pub const ALICE: u128 = 1;
+pub const BOB: u128 = 2;
+pub const CHARLIE: u128 = 3;
+pub const DAVE: u128 = 4;
+//Sources from here on are account indices, the way pallet_indices would resolve them
+pub const FIRST_INDEX: u128 = 1_000;
+
+parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+	pub const SS58Prefix: u8 = 42;
+}
+
+impl frame_system::Config for Test {
+	type BaseCallFilter = ();
+	type BlockWeights = ();
+	type BlockLength = ();
+	type DbWeight = ();
+	type Origin = Origin;
+	type Call = Call;
+	type Index = u64;
+	type BlockNumber = u64;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = u128;
+	type Lookup = IndexLookup;
+	type Header = Header;
+	type Event = Event;
+	type BlockHashCount = BlockHashCount;
+	type Version = ();
+	type PalletInfo = PalletInfo;
+	type AccountData = pallet_balances::AccountData<u64>;
+	type OnNewAccount = ();
+	type OnKilledAccount = Erc20;
+	type SystemWeightInfo = ();
+	type SS58Prefix = SS58Prefix;
+}
+
+parameter_types! {
+	pub const ExistentialDeposit: u64 = 1;
+}
+
+impl pallet_balances::Config for Test {
+	type MaxLocks = ();
+	type Balance = u64;
+	type Event = Event;
+	type DustRemoval = ();
+	type ExistentialDeposit = ExistentialDeposit;
+	type AccountStore = System;
+	type WeightInfo = ();
+}
+
+parameter_types! {
+	pub const MaxDecimals: u8 = 18;
+	pub const AuditBatchSize: u32 = 2;
+	pub const MaxAllowanceRemovals: u32 = 2;
+	pub const MaxBatchSize: u32 = 5;
+	pub const MaxMemoLength: u32 = 8;
+	pub const ReferenceRetention: u64 = 10;
+	pub const MaxReferencesPerBlock: u32 = 2;
+	pub const MaxUriLength: u32 = 16;
+	pub const TokenModuleId: ModuleId = ModuleId(*b"py/erc20");
+	pub const TransferLimitDelay: u64 = 5;
+	pub const MaxLocks: u32 = 3;
+	pub const MaxVestingSchedules: u32 = 2;
+	pub const MinVestedTransfer: u64 = 10;
+	pub const MinLockedTransfer: u64 = 10;
+	pub const MaxScheduledPerBlock: u32 = 2;
+	pub const MaxCheckpoints: u32 = 8;
+	pub const LockPeriod: u64 = 10;
+	pub const MaxImportChunk: u32 = 3;
+	pub const MaxAirdropSize: u32 = 3;
+	pub const AccountDeposit: u64 = 10;
+}
+
+//Settings individual tests change; new_test_ext resets them
+thread_local! {
+	static DORMANT_DESTINATION: RefCell<Option<u128>> = RefCell::new(None);
+	static APPROVAL_DELAY: RefCell<u64> = RefCell::new(0);
+	static UNBONDING_PERIOD: RefCell<u64> = RefCell::new(0);
+	static INDICES: RefCell<Vec<u128>> = RefCell::new(Vec::new());
+}
+
+//Resolves the sources from FIRST_INDEX up to the accounts registered with add_index, in
+//order; any other source is taken as the account id itself
+pub struct IndexLookup;
+impl StaticLookup for IndexLookup {
+	type Source = u128;
+	type Target = u128;
+
+	fn lookup(source: u128) -> Result<u128, LookupError> {
+		if source < FIRST_INDEX || source >= FIRST_INDEX + 1_000 {
+			return Ok(source);
+		}
+		INDICES.with(|indices| indices.borrow().get((source - FIRST_INDEX) as usize).copied()).ok_or(LookupError)
+	}
+
+	fn unlookup(target: u128) -> u128 {
+		target
+	}
+}
+
+//Gives 'who' the next account index and returns it as a lookup source
+pub fn add_index(who: u128) -> u128 {
+	INDICES.with(|indices| {
+		let mut indices = indices.borrow_mut();
+		indices.push(who);
+		FIRST_INDEX + indices.len() as u128 - 1
+	})
+}
+
+pub struct DormantDestination;
+impl Get<Option<u128>> for DormantDestination {
+	fn get() -> Option<u128> {
+		DORMANT_DESTINATION.with(|value| *value.borrow())
+	}
+}
+
+pub struct ApprovalDelay;
+impl Get<u64> for ApprovalDelay {
+	fn get() -> u64 {
+		APPROVAL_DELAY.with(|value| *value.borrow())
+	}
+}
+
+pub struct UnbondingPeriod;
+impl Get<u64> for UnbondingPeriod {
+	fn get() -> u64 {
+		UNBONDING_PERIOD.with(|value| *value.borrow())
+	}
+}
+
+pub fn set_dormant_destination(destination: Option<u128>) {
+	DORMANT_DESTINATION.with(|value| *value.borrow_mut() = destination);
+}
+
+pub fn set_approval_delay(delay: u64) {
+	APPROVAL_DELAY.with(|value| *value.borrow_mut() = delay);
+}
+
+pub fn set_unbonding_period(period: u64) {
+	UNBONDING_PERIOD.with(|value| *value.borrow_mut() = period);
+}
+
+impl simple_erc20::Config for Test {
+	type Event = Event;
+	type MaxDecimals = MaxDecimals;
+	type AuditBatchSize = AuditBatchSize;
+	type DormantDestination = DormantDestination;
+	type MaxAllowanceRemovals = MaxAllowanceRemovals;
+	type ApprovalDelay = ApprovalDelay;
+	type MaxBatchSize = MaxBatchSize;
+	type MaxMemoLength = MaxMemoLength;
+	type ReferenceRetention = ReferenceRetention;
+	type MaxReferencesPerBlock = MaxReferencesPerBlock;
+	type MaxUriLength = MaxUriLength;
+	type ModuleId = TokenModuleId;
+	type TransferLimitDelay = TransferLimitDelay;
+	type MaxLocks = MaxLocks;
+	type MaxVestingSchedules = MaxVestingSchedules;
+	type MinVestedTransfer = MinVestedTransfer;
+	type MinLockedTransfer = MinLockedTransfer;
+	type MaxScheduledPerBlock = MaxScheduledPerBlock;
+	type MaxCheckpoints = MaxCheckpoints;
+	type UnbondingPeriod = UnbondingPeriod;
+	type LockPeriod = LockPeriod;
+	type MaxImportChunk = MaxImportChunk;
+	type MaxAirdropSize = MaxAirdropSize;
+	type Currency = NativeBalances;
+	type AccountDeposit = AccountDeposit;
+}
+
+impl locker::Config for Test {
+	type Locks = Erc20;
+}
+
+pub type TokenEvent = simple_erc20::Event<Test>;
+
+//Every named account starts with 10_000 of the native currency and no tokens, at block 1
+pub fn new_test_ext() -> sp_io::TestExternalities {
+	set_dormant_destination(None);
+	set_approval_delay(0);
+	set_unbonding_period(0);
+	INDICES.with(|indices| indices.borrow_mut().clear());
+
+	let mut storage = frame_system::GenesisConfig::default().build_storage::<Test>().unwrap();
+	pallet_balances::GenesisConfig::<Test> {
+		balances: vec![(ALICE, 10_000), (BOB, 10_000), (CHARLIE, 10_000), (DAVE, 10_000)],
+	}
+	.assimilate_storage(&mut storage)
+	.unwrap();
+
+	let mut ext = sp_io::TestExternalities::new(storage);
+	ext.execute_with(|| System::set_block_number(1));
+	ext
+}
+
+//Runs 'test' and then checks the pallet invariants on whatever state it left behind
+pub fn run_test(test: impl FnOnce()) {
+	new_test_ext().execute_with(|| {
+		test();
+		assert_eq!(Erc20::do_try_state(), Ok(()));
+	});
+}
+
+//Starts every block up to and including 'n'
+pub fn run_to_block(n: u64) {
+	while System::block_number() < n {
+		let next = System::block_number() + 1;
+		System::set_block_number(next);
+		Erc20::on_initialize(next);
+	}
+}
+
+pub fn has_event(event: TokenEvent) -> bool {
+	let event: Event = event.into();
+	System::events().iter().any(|record| record.event == event)
+}
@@ -0,0 +1,1407 @@
+use crate::{
+	mock::*, Allowance, BalanceLock, BatchMode, DustPolicy, FaucetConfig, InflationConfig, MyError, Releases, Role,
+	StorageVersion, TokenLocks, TotalIssuance, TotalReserved, TransferFee, TransferPolicy,
+};
+use frame_support::{
+	assert_err, assert_noop, assert_ok,
+	traits::{OnKilledAccount, OnRuntimeUpgrade},
+	weights::GetDispatchInfo,
+	StorageMap, StorageValue,
+};
+use parity_scale_codec::{Compact, Decode, Encode};
+use sp_io::hashing::{blake2_256, keccak_256};
+use sp_runtime::DispatchError;
+
+//Mints the token to ALICE with a cap of 1_000_000
+fn mint_token(initial: u64, min_balance: u64) {
+	assert_ok!(Erc20::mint(Origin::signed(ALICE), b"Token".to_vec(), b"tkn".to_vec(), 1_000_000, initial, 2, min_balance));
+}
+
+//Mints 100_000 to ALICE and gives BOB and CHARLIE 1_000 each
+fn setup() {
+	mint_token(100_000, 1);
+	assert_ok!(Erc20::transfer(Origin::signed(ALICE), BOB, 1_000));
+	assert_ok!(Erc20::transfer(Origin::signed(ALICE), CHARLIE, 1_000));
+}
+
+fn claim_leaf(who: u128, amount: u64) -> [u8; 32] {
+	blake2_256(&(&who, amount).encode())
+}
+
+fn hash_pair(a: [u8; 32], b: [u8; 32]) -> [u8; 32] {
+	let (first, second) = if a <= b { (a, b) } else { (b, a) };
+	let mut pair = [0u8; 64];
+	pair[..32].copy_from_slice(&first);
+	pair[32..].copy_from_slice(&second);
+	blake2_256(&pair)
+}
+
+#[test]
+fn mint_sets_up_the_token() {
+	run_test(|| {
+		mint_token(100_000, 1);
+		assert_eq!(Erc20::get_ticker(), b"TKN".to_vec());
+		assert_eq!(Erc20::get_name(), b"Token".to_vec());
+		assert_eq!(Erc20::get_max_supply(), 1_000_000);
+		assert_eq!(Erc20::get_total_issuance(), 100_000);
+		assert_eq!(Erc20::free_balance(&ALICE), 100_000);
+		assert_eq!(Erc20::get_owner(), Some(ALICE));
+		assert!(has_event(TokenEvent::OwnerSet(ALICE)));
+		assert!(has_event(TokenEvent::Minted(ALICE, b"TKN".to_vec(), 1_000_000)));
+	});
+}
+
+#[test]
+fn mint_validates_its_input() {
+	run_test(|| {
+		let mint = |name: &[u8], ticker: &[u8], supply, initial, decimals| {
+			Erc20::mint(Origin::signed(ALICE), name.to_vec(), ticker.to_vec(), supply, initial, decimals, 1)
+		};
+		assert_noop!(mint(b"Token", b"tkn", 0, 0, 2), MyError::<Test>::ZeroSupply);
+		assert_noop!(mint(b"", b"tkn", 100, 0, 2), MyError::<Test>::EmptyName);
+		assert_noop!(mint(b"Token", b"t-n", 100, 0, 2), MyError::<Test>::InvalidTicker);
+		assert_noop!(mint(b"Token", b"tkn", 100, 0, 19), MyError::<Test>::TooManyDecimals);
+		assert_noop!(mint(b"Token", b"tkn", 100, 101, 2), MyError::<Test>::CapExceeded);
+		assert_ok!(mint(b"Token", b"tkn", 100, 100, 2));
+		assert_noop!(mint(b"Token", b"tkn", 100, 0, 2), MyError::<Test>::AlreadyMinted);
+	});
+}
+
+#[test]
+fn mint_with_distribution_issues_every_allocation() {
+	run_test(|| {
+		assert_noop!(
+			Erc20::mint_with_distribution(Origin::signed(ALICE), b"Token".to_vec(), b"tkn".to_vec(), 5, vec![(BOB, 5)], 2, 10),
+			MyError::<Test>::BelowMinimum
+		);
+		assert_noop!(
+			Erc20::mint_with_distribution(Origin::signed(ALICE), b"Token".to_vec(), b"tkn".to_vec(), 1_000, vec![(BOB, 300)], 2, 10),
+			MyError::<Test>::DistributionMismatch
+		);
+		assert_ok!(Erc20::mint_with_distribution(
+			Origin::signed(ALICE),
+			b"Token".to_vec(),
+			b"tkn".to_vec(),
+			500,
+			vec![(BOB, 300), (CHARLIE, 200)],
+			2,
+			10
+		));
+		assert_eq!(Erc20::free_balance(&BOB), 300);
+		assert_eq!(Erc20::free_balance(&CHARLIE), 200);
+		assert_eq!(Erc20::get_total_issuance(), 500);
+	});
+}
+
+#[test]
+fn a_failed_mint_writes_no_metadata() {
+	run_test(|| {
+		//The second allocation goes over the cap after the first was issued
+		assert_noop!(
+			Erc20::do_mint(ALICE, b"Token".to_vec(), b"tkn".to_vec(), 100, 2, 1, &[(BOB, 60), (CHARLIE, 60)]),
+			MyError::<Test>::CapExceeded
+		);
+		assert!(!Erc20::get_mint());
+		mint_token(100_000, 1);
+	});
+}
+
+#[test]
+fn queries_emit_what_they_read() {
+	run_test(|| {
+		setup();
+		assert_ok!(Erc20::name(Origin::signed(BOB)));
+		assert!(has_event(TokenEvent::NameReturned(BOB, b"Token".to_vec())));
+		assert_ok!(Erc20::balance_of(Origin::signed(CHARLIE), BOB));
+		assert!(has_event(TokenEvent::BalanceReturned(BOB, 1_000)));
+		assert_ok!(Erc20::allowance(Origin::signed(DAVE), BOB, CHARLIE));
+		assert!(has_event(TokenEvent::AllowanceReturned(BOB, CHARLIE, Allowance::default())));
+	});
+}
+
+#[test]
+fn transfer_moves_balances_and_numbers_every_transfer() {
+	run_test(|| {
+		setup();
+		assert_ok!(Erc20::transfer(Origin::signed(BOB), DAVE, 400));
+		assert_eq!(Erc20::free_balance(&BOB), 600);
+		assert_eq!(Erc20::free_balance(&DAVE), 400);
+		assert!(has_event(TokenEvent::Transfer(0, ALICE, BOB, 1_000)));
+		assert!(has_event(TokenEvent::Transfer(2, BOB, DAVE, 400)));
+		assert_eq!(Erc20::transfer_counter(), 3);
+	});
+}
+
+#[test]
+fn transfer_rejects_bad_requests() {
+	run_test(|| {
+		setup();
+		assert_noop!(Erc20::transfer(Origin::signed(BOB), DAVE, 1_001), MyError::<Test>::NotEnoughFunds);
+		assert_noop!(Erc20::transfer(Origin::signed(BOB), DAVE, 0), MyError::<Test>::ZeroAmount);
+		assert_noop!(Erc20::transfer(Origin::signed(BOB), BOB, 10), MyError::<Test>::SelfTransfer);
+		assert_noop!(Erc20::transfer(Origin::signed(DAVE), BOB, 10), MyError::<Test>::NoValueStored);
+		assert_noop!(Erc20::mint_to(Origin::signed(ALICE), BOB, u64::MAX), MyError::<Test>::Overflow);
+	});
+}
+
+#[test]
+fn destinations_resolve_through_the_lookup() {
+	run_test(|| {
+		setup();
+		let dave_index = add_index(DAVE);
+		assert_ok!(Erc20::transfer(Origin::signed(BOB), DAVE, 100));
+		assert_ok!(Erc20::transfer(Origin::signed(BOB), dave_index, 100));
+		assert_eq!(Erc20::free_balance(&DAVE), 200);
+		assert_ok!(Erc20::approve(Origin::signed(BOB), dave_index, 50));
+		assert_ok!(Erc20::transfer_from(Origin::signed(DAVE), BOB, dave_index, 50));
+		assert_eq!(Erc20::free_balance(&DAVE), 250);
+		assert_noop!(Erc20::transfer(Origin::signed(BOB), dave_index + 1, 100), DispatchError::CannotLookup);
+	});
+}
+
+#[test]
+fn transfers_into_a_full_account_fail_cleanly() {
+	new_test_ext().execute_with(|| {
+		setup();
+		//More than the cap allows, so the next credit has to overflow
+		crate::Balances::<Test>::insert(&BOB, u64::MAX);
+		assert_noop!(Erc20::transfer(Origin::signed(ALICE), BOB, 1), MyError::<Test>::Overflow);
+		assert_eq!(Erc20::free_balance(&ALICE), 98_000);
+		assert_eq!(Erc20::free_balance(&BOB), u64::MAX);
+	});
+}
+
+#[test]
+fn amounts_are_compact_encoded() {
+	let call = crate::Call::<Test>::transfer(BOB, 1_000_000);
+	let encoded = call.encode();
+	//Call index and destination as before, then the amount in four bytes instead of eight
+	let old_format = (encoded[0], BOB, 1_000_000u64).encode();
+	assert_eq!(encoded, (encoded[0], BOB, Compact(1_000_000u64)).encode());
+	assert_eq!(old_format.len(), encoded.len() + 4);
+	assert_eq!(crate::Call::<Test>::decode(&mut &encoded[..]).ok(), Some(call.clone()));
+	assert_ne!(crate::Call::<Test>::decode(&mut &old_format[..]).ok(), Some(call));
+}
+
+#[test]
+fn failed_transfers_leave_no_storage_changes() {
+	run_test(|| {
+		setup();
+		assert_ok!(Erc20::set_transfer_fee(Origin::signed(ALICE), Some(TransferFee { rate_bps: 100 })));
+		assert_ok!(Erc20::set_lock(*b"testlock", &BOB, 600, None));
+		assert_ok!(Erc20::approve(Origin::signed(BOB), CHARLIE, 500));
+		//The fee is already taken and the allowance checked when the lock check fails
+		assert_noop!(Erc20::transfer(Origin::signed(BOB), DAVE, 450), MyError::<Test>::LiquidityRestrictions);
+		assert_noop!(Erc20::transfer_from(Origin::signed(CHARLIE), BOB, DAVE, 450), MyError::<Test>::LiquidityRestrictions);
+		assert_eq!(Erc20::free_balance(&Erc20::treasury_account()), 0);
+	});
+}
+
+#[test]
+fn emptied_accounts_leave_no_entry_and_give_up_their_provider() {
+	run_test(|| {
+		setup();
+		assert_ok!(Erc20::transfer(Origin::signed(ALICE), 10, 100));
+		assert_eq!(System::account(&10).providers, 1);
+		assert_ok!(Erc20::transfer_all(Origin::signed(10), ALICE));
+		assert!(!crate::Balances::<Test>::contains_key(&10));
+		assert_eq!(System::account(&10).providers, 0);
+	});
+}
+
+#[test]
+fn transfer_keep_alive_never_empties_the_sender() {
+	run_test(|| {
+		setup();
+		assert_noop!(Erc20::transfer_keep_alive(Origin::signed(BOB), DAVE, 1_000), MyError::<Test>::WouldKill);
+		assert_ok!(Erc20::transfer_keep_alive(Origin::signed(BOB), DAVE, 999));
+	});
+}
+
+#[test]
+fn encoded_transfers_use_compact_amounts() {
+	run_test(|| {
+		//Call index, the account id and a single byte for a small compact amount
+		assert_eq!(crate::Call::<Test>::transfer(BOB, 1).encode().len(), 1 + 16 + 1);
+	});
+}
+
+#[test]
+fn try_state_catches_a_broken_issuance() {
+	new_test_ext().execute_with(|| {
+		setup();
+		assert_eq!(Erc20::do_try_state(), Ok(()));
+		TotalIssuance::put(99_999);
+		assert!(Erc20::do_try_state().is_err());
+	});
+}
+
+#[test]
+fn the_v7_migration_recomputes_issuance_from_balances() {
+	run_test(|| {
+		setup();
+		assert_ok!(Erc20::force_reserve(Origin::root(), BOB, 400));
+		TotalIssuance::put(0);
+		TotalReserved::put(0);
+		StorageVersion::put(Releases::V6);
+		Erc20::on_runtime_upgrade();
+		assert_eq!(Erc20::get_total_shares(), 100_000);
+		assert_eq!(Erc20::get_total_reserved_shares(), 400);
+		assert_eq!(Erc20::storage_version(), Releases::V7);
+	});
+}
+
+#[test]
+fn audit_supply_walks_holders_in_batches() {
+	run_test(|| {
+		setup();
+		assert_ok!(Erc20::audit_supply(Origin::root()));
+		assert!(Erc20::audit_cursor().is_some());
+		assert_noop!(Erc20::audit_supply(Origin::signed(BOB)), MyError::<Test>::NotOwner);
+		assert_ok!(Erc20::audit_supply(Origin::signed(ALICE)));
+		assert!(has_event(TokenEvent::AuditCompleted(100_000, 100_000, 3)));
+		assert!(Erc20::audit_cursor().is_none());
+	});
+}
+
+#[test]
+fn reaped_accounts_are_burned_without_a_dormant_destination() {
+	run_test(|| {
+		setup();
+		assert_ok!(Erc20::force_reserve(Origin::root(), BOB, 400));
+		assert_ok!(Erc20::take_snapshot(Origin::signed(ALICE)));
+		Erc20::on_killed_account(&BOB);
+		assert_eq!(Erc20::free_balance(&BOB), 0);
+		assert_eq!(Erc20::reserved_balance(&BOB), 0);
+		assert_eq!(Erc20::get_total_issuance(), 99_000);
+		assert_eq!(Erc20::get_votes(&BOB), 0);
+		assert_eq!(Erc20::balance_at_snapshot(1, &BOB), 600);
+		assert!(has_event(TokenEvent::Burned(BOB, 1_000)));
+	});
+}
+
+#[test]
+fn reaped_accounts_are_swept_to_the_dormant_destination() {
+	run_test(|| {
+		setup();
+		set_dormant_destination(Some(DAVE));
+		assert_ok!(Erc20::force_reserve(Origin::root(), BOB, 400));
+		Erc20::on_killed_account(&BOB);
+		assert_eq!(Erc20::free_balance(&DAVE), 1_000);
+		assert_eq!(Erc20::get_votes(&DAVE), 1_000);
+		assert_eq!(Erc20::get_total_issuance(), 100_000);
+		assert!(has_event(TokenEvent::DormantSwept(BOB, 1_000)));
+	});
+}
+
+#[test]
+fn transfer_from_spends_the_allowance() {
+	run_test(|| {
+		setup();
+		assert_ok!(Erc20::approve(Origin::signed(BOB), CHARLIE, 300));
+		assert_ok!(Erc20::transfer_from(Origin::signed(CHARLIE), BOB, DAVE, 200));
+		assert_eq!(Erc20::free_balance(&DAVE), 200);
+		assert_eq!(Erc20::allowances(&BOB, &CHARLIE).map(|allowance| allowance.amount), Some(100));
+		assert!(has_event(TokenEvent::TransferFrom(BOB, CHARLIE, DAVE, 200, 100)));
+		assert_noop!(Erc20::transfer_from(Origin::signed(CHARLIE), BOB, DAVE, 101), MyError::<Test>::NotEnoughAllowance);
+		assert_noop!(Erc20::transfer_from(Origin::signed(DAVE), BOB, DAVE, 1), MyError::<Test>::NotEnoughAllowance);
+	});
+}
+
+#[test]
+fn allowances_with_uses_run_out() {
+	run_test(|| {
+		setup();
+		assert_ok!(Erc20::approve_with_uses(Origin::signed(BOB), CHARLIE, 100, 2));
+		assert_ok!(Erc20::transfer_from(Origin::signed(CHARLIE), BOB, DAVE, 10));
+		assert_ok!(Erc20::transfer_from(Origin::signed(CHARLIE), BOB, DAVE, 10));
+		assert!(has_event(TokenEvent::ApprovalExhausted(BOB, CHARLIE)));
+		assert!(Erc20::allowances(&BOB, &CHARLIE).is_none());
+	});
+}
+
+#[test]
+fn allowances_with_expiry_or_destination_are_enforced() {
+	run_test(|| {
+		setup();
+		assert_ok!(Erc20::approve_with_expiry(Origin::signed(BOB), CHARLIE, 100, 3));
+		run_to_block(4);
+		assert_noop!(Erc20::transfer_from(Origin::signed(CHARLIE), BOB, DAVE, 10), MyError::<Test>::AllowanceExpired);
+
+		assert_ok!(Erc20::approve_restricted(Origin::signed(BOB), CHARLIE, 100, DAVE));
+		assert_noop!(Erc20::transfer_from(Origin::signed(CHARLIE), BOB, ALICE, 10), MyError::<Test>::DestinationNotAllowed);
+		assert_ok!(Erc20::transfer_from(Origin::signed(CHARLIE), BOB, DAVE, 10));
+	});
+}
+
+#[test]
+fn allowances_with_a_rate_are_limited_per_period() {
+	run_test(|| {
+		setup();
+		assert_ok!(Erc20::approve_with_rate(Origin::signed(BOB), CHARLIE, 100, 10, 5));
+		assert_ok!(Erc20::transfer_from(Origin::signed(CHARLIE), BOB, DAVE, 10));
+		assert_noop!(Erc20::transfer_from(Origin::signed(CHARLIE), BOB, DAVE, 1), MyError::<Test>::RateLimited);
+		run_to_block(6);
+		assert_ok!(Erc20::transfer_from(Origin::signed(CHARLIE), BOB, DAVE, 10));
+	});
+}
+
+#[test]
+fn allowances_wait_for_the_approval_delay() {
+	run_test(|| {
+		setup();
+		set_approval_delay(3);
+		assert_ok!(Erc20::approve(Origin::signed(BOB), CHARLIE, 100));
+		assert_noop!(Erc20::transfer_from(Origin::signed(CHARLIE), BOB, DAVE, 10), MyError::<Test>::ApprovalNotActive);
+		run_to_block(4);
+		assert_ok!(Erc20::transfer_from(Origin::signed(CHARLIE), BOB, DAVE, 10));
+	});
+}
+
+#[test]
+fn allowances_are_adjusted_checked_and_revoked() {
+	run_test(|| {
+		setup();
+		assert_ok!(Erc20::approve(Origin::signed(BOB), CHARLIE, 100));
+		assert_noop!(Erc20::approve_checked(Origin::signed(BOB), CHARLIE, 99, 50), MyError::<Test>::AllowanceMismatch);
+		assert_ok!(Erc20::approve_checked(Origin::signed(BOB), CHARLIE, 100, 50));
+		assert_ok!(Erc20::increase_allowance(Origin::signed(BOB), CHARLIE, 25));
+		assert_ok!(Erc20::decrease_allowance(Origin::signed(BOB), CHARLIE, 100, true));
+		assert_eq!(Erc20::allowances(&BOB, &CHARLIE).map_or(0, |allowance| allowance.amount), 0);
+
+		assert_ok!(Erc20::batch_approve(Origin::signed(BOB), vec![(CHARLIE, 10), (DAVE, 20)]));
+		assert_ok!(Erc20::revoke_allowance(Origin::signed(BOB), CHARLIE));
+		assert!(Erc20::allowances(&BOB, &CHARLIE).is_none());
+		assert_ok!(Erc20::renounce_allowance(Origin::signed(DAVE), BOB));
+		assert!(Erc20::allowances(&BOB, &DAVE).is_none());
+	});
+}
+
+#[test]
+fn burn_and_burn_from_lower_the_supply() {
+	run_test(|| {
+		setup();
+		assert_ok!(Erc20::burn(Origin::signed(BOB), 100));
+		assert_eq!(Erc20::get_total_issuance(), 99_900);
+		assert_ok!(Erc20::approve(Origin::signed(BOB), CHARLIE, 100));
+		assert_ok!(Erc20::burn_from(Origin::signed(CHARLIE), BOB, 60));
+		assert!(has_event(TokenEvent::BurnedFrom(BOB, CHARLIE, 60, 40)));
+		assert_eq!(Erc20::free_balance(&BOB), 840);
+		assert_eq!(Erc20::get_max_supply(), 1_000_000);
+	});
+}
+
+#[test]
+fn mint_to_needs_the_owner_or_a_minter() {
+	run_test(|| {
+		setup();
+		assert_noop!(Erc20::mint_to(Origin::signed(BOB), BOB, 10), MyError::<Test>::MissingRole);
+		assert_ok!(Erc20::grant_role(Origin::signed(ALICE), BOB, Role::Minter));
+		assert_ok!(Erc20::mint_to(Origin::signed(BOB), DAVE, 10));
+		assert_eq!(Erc20::free_balance(&DAVE), 10);
+		assert_noop!(Erc20::mint_to(Origin::signed(ALICE), DAVE, 900_000), MyError::<Test>::CapExceeded);
+	});
+}
+
+#[test]
+fn minter_quotas_run_out_and_expire() {
+	run_test(|| {
+		setup();
+		assert_ok!(Erc20::set_minter(Origin::signed(ALICE), BOB, 50, 5));
+		assert_noop!(Erc20::mint_quota(Origin::signed(BOB), DAVE, 51), MyError::<Test>::QuotaExceeded);
+		assert_ok!(Erc20::mint_quota(Origin::signed(BOB), DAVE, 30));
+		assert_eq!(Erc20::minters(&BOB).map(|quota| quota.remaining), Some(20));
+		run_to_block(6);
+		assert_noop!(Erc20::mint_quota(Origin::signed(BOB), DAVE, 10), MyError::<Test>::MinterExpired);
+	});
+}
+
+#[test]
+fn inflation_is_paid_every_block_until_its_end() {
+	run_test(|| {
+		setup();
+		let config = InflationConfig { per_block: 5, beneficiary: DAVE, end_block: Some(3) };
+		assert_ok!(Erc20::set_inflation(Origin::signed(ALICE), Some(config)));
+		run_to_block(5);
+		assert_eq!(Erc20::free_balance(&DAVE), 10);
+		assert_eq!(Erc20::get_total_issuance(), 100_010);
+	});
+}
+
+#[test]
+fn batch_transfers_debit_the_sender_once_for_every_credit() {
+	run_test(|| {
+		setup();
+		assert_ok!(Erc20::batch_transfer(Origin::signed(BOB), vec![(DAVE, 100), (CHARLIE, 50), (DAVE, 200)], BatchMode::Atomic));
+		assert_eq!(Erc20::free_balance(&BOB), 650);
+		assert_eq!(Erc20::free_balance(&DAVE), 300);
+		assert_eq!(Erc20::free_balance(&CHARLIE), 1_050);
+		assert_noop!(Erc20::batch_transfer(Origin::signed(BOB), vec![], BatchMode::Atomic), MyError::<Test>::EmptyBatch);
+		assert_noop!(
+			Erc20::batch_transfer(Origin::signed(BOB), vec![(DAVE, 400), (CHARLIE, 300)], BatchMode::Atomic),
+			MyError::<Test>::NotEnoughFunds
+		);
+	});
+}
+
+#[test]
+fn batch_weights_stop_growing_at_the_batch_limit() {
+	let weight = |recipients: usize| {
+		crate::Call::<Test>::batch_transfer(vec![(DAVE, 1); recipients], BatchMode::Atomic).get_dispatch_info().weight
+	};
+	assert_eq!(weight(2), 30_000);
+	assert_eq!(weight(1_000), 60_000);
+	let airdrop = crate::Call::<Test>::airdrop(vec![(DAVE, 1); 1_000]).get_dispatch_info().weight;
+	assert_eq!(airdrop, 40_000);
+}
+
+#[test]
+fn batch_transfers_are_atomic_or_best_effort() {
+	run_test(|| {
+		setup();
+		assert_noop!(
+			Erc20::batch_transfer(Origin::signed(BOB), vec![(CHARLIE, 100), (DAVE, 0)], BatchMode::Atomic),
+			MyError::<Test>::ZeroAmount
+		);
+		assert_ok!(Erc20::batch_transfer(Origin::signed(BOB), vec![(CHARLIE, 100), (DAVE, 0)], BatchMode::BestEffort));
+		assert!(has_event(TokenEvent::BatchCompleted(1, 1)));
+		assert_eq!(Erc20::free_balance(&CHARLIE), 1_100);
+		assert_noop!(
+			Erc20::batch_transfer(Origin::signed(BOB), vec![(CHARLIE, 1); 6], BatchMode::Atomic),
+			MyError::<Test>::BatchTooLarge
+		);
+	});
+}
+
+#[test]
+fn a_failing_item_rolls_back_or_skips_depending_on_the_mode() {
+	run_test(|| {
+		setup();
+		let recipients = vec![(CHARLIE, 10), (DAVE, 10), (DAVE, 0), (CHARLIE, 10), (DAVE, 10)];
+		assert_noop!(
+			Erc20::batch_transfer(Origin::signed(BOB), recipients.clone(), BatchMode::Atomic),
+			MyError::<Test>::ZeroAmount
+		);
+		assert_eq!(Erc20::free_balance(&BOB), 1_000);
+		assert_ok!(Erc20::batch_transfer(Origin::signed(BOB), recipients, BatchMode::BestEffort));
+		assert!(has_event(TokenEvent::BatchItemFailed(2, MyError::<Test>::ZeroAmount.into())));
+		assert!(has_event(TokenEvent::BatchCompleted(4, 1)));
+		assert_eq!(Erc20::free_balance(&BOB), 960);
+		assert_eq!(Erc20::free_balance(&DAVE), 20);
+	});
+}
+
+#[test]
+fn sweep_approved_and_pull_spend_allowances() {
+	run_test(|| {
+		setup();
+		assert_ok!(Erc20::approve(Origin::signed(BOB), DAVE, 300));
+		assert_ok!(Erc20::pull(Origin::signed(DAVE), BOB, 100));
+		assert_eq!(Erc20::free_balance(&DAVE), 100);
+		assert_ok!(Erc20::sweep_approved(Origin::signed(DAVE), vec![BOB, CHARLIE], DAVE));
+		assert!(has_event(TokenEvent::SweepSkipped(CHARLIE)));
+		assert_eq!(Erc20::free_balance(&DAVE), 300);
+		assert_eq!(Erc20::free_balance(&BOB), 700);
+	});
+}
+
+#[test]
+fn memos_are_limited_and_can_be_required() {
+	run_test(|| {
+		setup();
+		assert_noop!(
+			Erc20::transfer_with_memo(Origin::signed(BOB), CHARLIE, 10, b"too long!".to_vec()),
+			MyError::<Test>::MemoTooLong
+		);
+		assert_ok!(Erc20::require_memo(Origin::signed(CHARLIE), true));
+		assert_noop!(Erc20::transfer(Origin::signed(BOB), CHARLIE, 10), MyError::<Test>::MemoRequired);
+		assert_ok!(Erc20::transfer_with_memo(Origin::signed(BOB), CHARLIE, 10, b"order 7".to_vec()));
+		assert!(has_event(TokenEvent::TransferWithMemo(BOB, CHARLIE, 10, b"order 7".to_vec())));
+	});
+}
+
+#[test]
+fn idempotent_transfers_refuse_a_reused_reference() {
+	run_test(|| {
+		setup();
+		assert_ok!(Erc20::transfer_idempotent(Origin::signed(BOB), DAVE, 10, [1; 32]));
+		assert_noop!(Erc20::transfer_idempotent(Origin::signed(BOB), DAVE, 10, [1; 32]), MyError::<Test>::DuplicateReference);
+		assert_ok!(Erc20::transfer_idempotent(Origin::signed(BOB), DAVE, 10, [2; 32]));
+		assert_noop!(Erc20::transfer_idempotent(Origin::signed(BOB), DAVE, 10, [3; 32]), MyError::<Test>::TooManyReferences);
+		run_to_block(11);
+		assert_ok!(Erc20::transfer_idempotent(Origin::signed(BOB), DAVE, 10, [1; 32]));
+		assert_eq!(Erc20::free_balance(&DAVE), 30);
+	});
+}
+
+#[test]
+fn ownership_moves_in_two_steps_and_can_be_renounced() {
+	run_test(|| {
+		setup();
+		assert_noop!(Erc20::transfer_ownership(Origin::signed(BOB), BOB), MyError::<Test>::NotOwner);
+		assert_ok!(Erc20::transfer_ownership(Origin::signed(ALICE), BOB));
+		assert_noop!(Erc20::accept_ownership(Origin::signed(CHARLIE)), MyError::<Test>::NotPendingOwner);
+		assert_ok!(Erc20::accept_ownership(Origin::signed(BOB)));
+		assert_eq!(Erc20::get_owner(), Some(BOB));
+
+		assert_noop!(Erc20::renounce_ownership(Origin::signed(BOB), b"abc".to_vec()), MyError::<Test>::RenounceNotConfirmed);
+		assert_ok!(Erc20::renounce_ownership(Origin::signed(BOB), b"tkn".to_vec()));
+		assert_eq!(Erc20::get_owner(), None);
+	});
+}
+
+#[test]
+fn pause_stops_transfers_until_unpaused() {
+	run_test(|| {
+		setup();
+		assert_ok!(Erc20::grant_role(Origin::signed(ALICE), BOB, Role::Pauser));
+		assert_ok!(Erc20::pause(Origin::signed(BOB)));
+		assert_noop!(Erc20::transfer(Origin::signed(CHARLIE), DAVE, 10), MyError::<Test>::TokenPaused);
+		assert_ok!(Erc20::unpause(Origin::signed(BOB)));
+		assert_ok!(Erc20::transfer(Origin::signed(CHARLIE), DAVE, 10));
+	});
+}
+
+#[test]
+fn halting_stops_every_call_until_resumed() {
+	run_test(|| {
+		setup();
+		assert_ok!(Erc20::force_halt(Origin::root()));
+		assert_noop!(Erc20::transfer(Origin::signed(BOB), DAVE, 10), MyError::<Test>::Halted);
+		assert_noop!(Erc20::approve(Origin::signed(BOB), DAVE, 10), MyError::<Test>::Halted);
+		assert_ok!(Erc20::force_resume(Origin::root()));
+		assert_ok!(Erc20::transfer(Origin::signed(BOB), DAVE, 10));
+	});
+}
+
+#[test]
+fn frozen_accounts_cannot_send() {
+	run_test(|| {
+		setup();
+		assert_ok!(Erc20::freeze_account(Origin::signed(ALICE), BOB, false));
+		assert_noop!(Erc20::transfer(Origin::signed(BOB), DAVE, 10), MyError::<Test>::AccountFrozen);
+		assert_ok!(Erc20::transfer(Origin::signed(CHARLIE), BOB, 10));
+		assert_ok!(Erc20::freeze_account(Origin::signed(ALICE), BOB, true));
+		assert_noop!(Erc20::transfer(Origin::signed(CHARLIE), BOB, 10), MyError::<Test>::AccountFrozen);
+		assert_ok!(Erc20::thaw_account(Origin::signed(ALICE), BOB));
+		assert_ok!(Erc20::transfer(Origin::signed(BOB), DAVE, 10));
+	});
+}
+
+#[test]
+fn blacklisted_accounts_can_be_confiscated() {
+	run_test(|| {
+		setup();
+		assert_noop!(Erc20::confiscate(Origin::root(), BOB, None), MyError::<Test>::NotBlacklisted);
+		assert_ok!(Erc20::add_to_blacklist(Origin::signed(ALICE), BOB));
+		assert_noop!(Erc20::transfer(Origin::signed(BOB), DAVE, 10), MyError::<Test>::SenderBlacklisted);
+		assert_noop!(Erc20::transfer(Origin::signed(CHARLIE), BOB, 10), MyError::<Test>::RecipientBlacklisted);
+		assert_ok!(Erc20::confiscate(Origin::root(), BOB, None));
+		assert_eq!(Erc20::free_balance(&BOB), 0);
+		assert_eq!(Erc20::get_total_issuance(), 99_000);
+	});
+}
+
+#[test]
+fn whitelist_only_needs_both_sides_whitelisted() {
+	run_test(|| {
+		setup();
+		assert_ok!(Erc20::set_transfer_policy(Origin::signed(ALICE), TransferPolicy::WhitelistOnly));
+		assert_ok!(Erc20::whitelist_add(Origin::signed(ALICE), BOB));
+		assert_noop!(Erc20::transfer(Origin::signed(BOB), DAVE, 10), MyError::<Test>::NotWhitelisted);
+		assert_ok!(Erc20::whitelist_add(Origin::signed(ALICE), DAVE));
+		assert_ok!(Erc20::transfer(Origin::signed(BOB), DAVE, 10));
+	});
+}
+
+#[test]
+fn soulbound_tokens_only_move_through_exempt_accounts() {
+	run_test(|| {
+		setup();
+		assert_ok!(Erc20::set_soulbound(Origin::signed(ALICE), true));
+		assert_noop!(Erc20::transfer(Origin::signed(BOB), DAVE, 10), MyError::<Test>::NonTransferable);
+		assert_ok!(Erc20::set_soulbound_exempt(Origin::signed(ALICE), BOB, true));
+		assert_ok!(Erc20::transfer(Origin::signed(BOB), DAVE, 10));
+	});
+}
+
+#[test]
+fn root_can_force_transfers_and_balances() {
+	run_test(|| {
+		setup();
+		assert_noop!(Erc20::force_transfer(Origin::signed(ALICE), BOB, DAVE, 10), DispatchError::BadOrigin);
+		assert_ok!(Erc20::force_transfer(Origin::root(), BOB, DAVE, 100));
+		assert_eq!(Erc20::free_balance(&DAVE), 100);
+
+		assert_noop!(Erc20::force_set_balance(Origin::root(), DAVE, 1_000_000, false), MyError::<Test>::CapExceeded);
+		assert_ok!(Erc20::force_set_balance(Origin::root(), DAVE, 500, false));
+		assert!(has_event(TokenEvent::BalanceSet(DAVE, 100, 500)));
+		assert_eq!(Erc20::get_total_issuance(), 100_400);
+		assert_ok!(Erc20::force_set_balance(Origin::root(), DAVE, 0, false));
+		assert_eq!(Erc20::get_total_issuance(), 99_900);
+	});
+}
+
+#[test]
+fn only_stranded_tokens_can_be_recovered() {
+	run_test(|| {
+		setup();
+		assert_ok!(Erc20::create_escrow(Origin::signed(BOB), DAVE, 100, 10));
+		assert_ok!(Erc20::transfer(Origin::signed(CHARLIE), Erc20::account_id(), 50));
+		assert_noop!(Erc20::recover_stranded(Origin::root(), CHARLIE, 51), MyError::<Test>::NothingToRecover);
+		assert_ok!(Erc20::recover_stranded(Origin::root(), CHARLIE, 50));
+		assert_eq!(Erc20::free_balance(&CHARLIE), 1_000);
+		assert_ok!(Erc20::claim_escrow(Origin::signed(DAVE), 0));
+	});
+}
+
+#[test]
+fn transfer_limits_tighten_at_once_and_loosen_after_a_delay() {
+	run_test(|| {
+		setup();
+		assert_ok!(Erc20::set_transfer_limit(Origin::signed(BOB), 100, 10));
+		assert_ok!(Erc20::transfer(Origin::signed(BOB), CHARLIE, 60));
+		assert_noop!(Erc20::transfer(Origin::signed(BOB), CHARLIE, 50), MyError::<Test>::TransferLimitExceeded);
+		assert_ok!(Erc20::set_transfer_limit(Origin::signed(BOB), 200, 10));
+		assert!(has_event(TokenEvent::TransferLimitScheduled(BOB, 200, 10, 6)));
+		assert_noop!(Erc20::transfer(Origin::signed(BOB), CHARLIE, 50), MyError::<Test>::TransferLimitExceeded);
+		run_to_block(6);
+		assert_ok!(Erc20::transfer(Origin::signed(BOB), CHARLIE, 50));
+	});
+}
+
+#[test]
+fn transfer_amounts_stay_within_bounds() {
+	run_test(|| {
+		setup();
+		assert_ok!(Erc20::set_max_transfer(Origin::signed(ALICE), Some(500)));
+		assert_noop!(Erc20::transfer(Origin::signed(BOB), DAVE, 501), MyError::<Test>::AboveMaxTransfer);
+		assert_ok!(Erc20::set_max_transfer(Origin::signed(ALICE), None));
+		assert_ok!(Erc20::set_min_transfer(Origin::signed(ALICE), 50));
+		assert_noop!(Erc20::transfer(Origin::signed(BOB), DAVE, 49), MyError::<Test>::BelowMinTransfer);
+		assert_ok!(Erc20::transfer(Origin::signed(BOB), DAVE, 980));
+		//Emptying the account is always allowed
+		assert_ok!(Erc20::transfer(Origin::signed(BOB), DAVE, 20));
+	});
+}
+
+#[test]
+fn the_circuit_breaker_pauses_the_token() {
+	run_test(|| {
+		setup();
+		assert_ok!(Erc20::set_max_volume_per_block(Origin::signed(ALICE), Some(1_500)));
+		assert_ok!(Erc20::transfer(Origin::signed(BOB), DAVE, 1_000));
+		assert_err!(Erc20::transfer(Origin::signed(CHARLIE), DAVE, 600), MyError::<Test>::CircuitBreaker);
+		assert!(Erc20::get_paused());
+		assert!(Erc20::get_breaker_tripped());
+
+		assert_ok!(Erc20::grant_role(Origin::signed(ALICE), DAVE, Role::Pauser));
+		assert_noop!(Erc20::unpause(Origin::signed(DAVE)), MyError::<Test>::NotOwner);
+		assert_ok!(Erc20::unpause(Origin::signed(ALICE)));
+		run_to_block(2);
+		assert_ok!(Erc20::transfer(Origin::signed(CHARLIE), DAVE, 600));
+	});
+}
+
+#[test]
+fn transfer_fees_go_to_the_treasury() {
+	run_test(|| {
+		setup();
+		assert_noop!(
+			Erc20::set_transfer_fee(Origin::signed(ALICE), Some(TransferFee { rate_bps: 10_001 })),
+			MyError::<Test>::FeeTooHigh
+		);
+		assert_ok!(Erc20::set_transfer_fee(Origin::signed(ALICE), Some(TransferFee { rate_bps: 100 })));
+		assert_ok!(Erc20::transfer(Origin::signed(BOB), CHARLIE, 1_000));
+		let treasury = Erc20::treasury_account();
+		assert_eq!(Erc20::free_balance(&CHARLIE), 1_990);
+		assert_eq!(Erc20::free_balance(&treasury), 10);
+		assert!(has_event(TokenEvent::FeeCharged(BOB, treasury, 10)));
+
+		assert_ok!(Erc20::treasury_transfer(Origin::signed(ALICE), DAVE, 10));
+		assert_eq!(Erc20::free_balance(&DAVE), 10);
+	});
+}
+
+#[test]
+fn burn_rates_destroy_part_of_each_transfer() {
+	run_test(|| {
+		setup();
+		assert_ok!(Erc20::set_burn_rate(Origin::signed(ALICE), 200));
+		assert_ok!(Erc20::transfer(Origin::signed(BOB), CHARLIE, 1_000));
+		assert_eq!(Erc20::free_balance(&CHARLIE), 1_980);
+		assert_eq!(Erc20::get_total_issuance(), 99_980);
+		assert!(has_event(TokenEvent::Burned(BOB, 20)));
+	});
+}
+
+#[test]
+fn deductions_never_let_zero_or_self_transfers_through() {
+	run_test(|| {
+		setup();
+		assert_ok!(Erc20::set_burn_rate(Origin::signed(ALICE), 200));
+		assert_ok!(Erc20::approve(Origin::signed(BOB), CHARLIE, 100));
+		assert_noop!(Erc20::transfer_from(Origin::signed(CHARLIE), BOB, DAVE, 0), MyError::<Test>::ZeroAmount);
+		assert_noop!(Erc20::transfer(Origin::signed(BOB), BOB, 500), MyError::<Test>::SelfTransfer);
+		assert_eq!(Erc20::allowances(&BOB, &CHARLIE).map(|allowance| allowance.amount), Some(100));
+	});
+}
+
+#[test]
+fn exempt_accounts_pay_no_deductions() {
+	run_test(|| {
+		setup();
+		assert_ok!(Erc20::set_transfer_fee(Origin::signed(ALICE), Some(TransferFee { rate_bps: 100 })));
+		assert_ok!(Erc20::exempt_add(Origin::signed(ALICE), BOB));
+		assert_ok!(Erc20::transfer(Origin::signed(BOB), CHARLIE, 500));
+		assert_eq!(Erc20::free_balance(&CHARLIE), 1_500);
+		assert_ok!(Erc20::exempt_remove(Origin::signed(ALICE), BOB));
+		assert_ok!(Erc20::transfer(Origin::signed(BOB), CHARLIE, 500));
+		assert_eq!(Erc20::free_balance(&CHARLIE), 1_995);
+		assert_noop!(Erc20::exempt_remove(Origin::signed(ALICE), DAVE), MyError::<Test>::NotExempt);
+	});
+}
+
+#[test]
+fn other_pallets_lock_tokens_through_the_trait() {
+	run_test(|| {
+		setup();
+		assert_ok!(Locker::lock(Origin::signed(BOB), 600, None));
+		assert_ok!(Locker::lock(Origin::signed(BOB), 300, None));
+		assert_eq!(Erc20::locked_balance(&BOB), 600);
+		assert_noop!(Erc20::transfer(Origin::signed(BOB), DAVE, 500), MyError::<Test>::LiquidityRestrictions);
+		assert_ok!(Locker::unlock(Origin::signed(BOB)));
+		assert_eq!(Erc20::locked_balance(&BOB), 0);
+		assert_ok!(Erc20::transfer(Origin::signed(BOB), DAVE, 500));
+	});
+}
+
+#[test]
+fn locks_hold_back_part_of_the_balance() {
+	run_test(|| {
+		setup();
+		assert_ok!(Erc20::set_lock(*b"testlock", &BOB, 600, None));
+		assert_noop!(Erc20::transfer(Origin::signed(BOB), DAVE, 500), MyError::<Test>::LiquidityRestrictions);
+		assert_ok!(Erc20::transfer(Origin::signed(BOB), DAVE, 400));
+		Erc20::remove_lock(*b"testlock", &BOB);
+		assert_ok!(Erc20::transfer(Origin::signed(BOB), DAVE, 600));
+
+		assert_ok!(Erc20::set_lock(*b"timelock", &CHARLIE, 1_000, Some(3)));
+		assert_noop!(Erc20::transfer(Origin::signed(CHARLIE), DAVE, 1), MyError::<Test>::LiquidityRestrictions);
+		run_to_block(3);
+		assert_ok!(Erc20::transfer(Origin::signed(CHARLIE), DAVE, 1));
+
+		assert_ok!(Erc20::set_lock(*b"lock0001", &ALICE, 10, None));
+		assert_ok!(Erc20::set_lock(*b"lock0002", &ALICE, 10, None));
+		assert_ok!(Erc20::set_lock(*b"lock0003", &ALICE, 10, None));
+		assert_noop!(Erc20::set_lock(*b"lock0004", &ALICE, 10, None), MyError::<Test>::TooManyLocks);
+	});
+}
+
+#[test]
+fn extend_lock_never_loosens_a_lock() {
+	run_test(|| {
+		setup();
+		assert_ok!(Erc20::set_lock(*b"testlock", &BOB, 600, None));
+		assert_ok!(<Erc20 as TokenLocks<u128, u64>>::extend_lock(*b"testlock", &BOB, 300, Some(10)));
+		assert_eq!(Erc20::locks(&BOB), vec![BalanceLock { id: *b"testlock", amount: 600, until: None }]);
+		assert_ok!(<Erc20 as TokenLocks<u128, u64>>::extend_lock(*b"testlock", &BOB, 800, None));
+		assert_eq!(Erc20::locked_balance(&BOB), 800);
+	});
+}
+
+#[test]
+fn reserved_tokens_leave_the_free_balance() {
+	run_test(|| {
+		setup();
+		assert_ok!(Erc20::force_reserve(Origin::root(), BOB, 400));
+		assert_eq!(Erc20::free_balance(&BOB), 600);
+		assert_eq!(Erc20::reserved_balance(&BOB), 400);
+		assert_noop!(Erc20::transfer(Origin::signed(BOB), DAVE, 601), MyError::<Test>::NotEnoughFunds);
+		assert_ok!(Erc20::force_unreserve(Origin::root(), BOB, 100));
+		assert_ok!(Erc20::force_slash_reserved(Origin::root(), BOB, 100));
+		assert_eq!(Erc20::free_balance(&BOB), 700);
+		assert_eq!(Erc20::reserved_balance(&BOB), 200);
+		assert_eq!(Erc20::get_total_issuance(), 99_900);
+	});
+}
+
+#[test]
+fn vested_transfers_unlock_over_time() {
+	run_test(|| {
+		setup();
+		assert_ok!(Erc20::vested_transfer(Origin::signed(BOB), DAVE, 100, 1, 10));
+		assert_noop!(Erc20::transfer(Origin::signed(DAVE), ALICE, 1), MyError::<Test>::LiquidityRestrictions);
+		run_to_block(5);
+		assert_ok!(Erc20::vest(Origin::signed(DAVE)));
+		assert_eq!(Erc20::locked_balance(&DAVE), 60);
+		assert_ok!(Erc20::transfer(Origin::signed(DAVE), ALICE, 40));
+		assert_noop!(Erc20::transfer(Origin::signed(DAVE), ALICE, 1), MyError::<Test>::LiquidityRestrictions);
+
+		assert_noop!(Erc20::vested_transfer(Origin::signed(BOB), DAVE, 9, 1, 1), MyError::<Test>::AmountLow);
+		assert_ok!(Erc20::vested_transfer(Origin::signed(BOB), DAVE, 10, 5, 1));
+		assert_noop!(Erc20::vested_transfer(Origin::signed(BOB), DAVE, 10, 5, 1), MyError::<Test>::TooManyVestingSchedules);
+	});
+}
+
+#[test]
+fn locked_transfers_unlock_at_their_block() {
+	run_test(|| {
+		setup();
+		assert_ok!(Erc20::transfer_locked(Origin::signed(BOB), DAVE, 50, 10));
+		assert_noop!(Erc20::transfer(Origin::signed(DAVE), ALICE, 1), MyError::<Test>::LiquidityRestrictions);
+		assert_noop!(Erc20::transfer_locked(Origin::signed(BOB), DAVE, 50, 1), MyError::<Test>::InvalidUnlockBlock);
+		run_to_block(10);
+		assert_ok!(Erc20::transfer(Origin::signed(DAVE), ALICE, 50));
+		assert_noop!(Erc20::transfer_locked(Origin::signed(BOB), DAVE, 9, 20), MyError::<Test>::AmountLow);
+	});
+}
+
+#[test]
+fn escrows_are_claimed_in_time_or_refunded() {
+	run_test(|| {
+		setup();
+		assert_ok!(Erc20::create_escrow(Origin::signed(BOB), DAVE, 100, 5));
+		assert_eq!(Erc20::get_earmarked(), 100);
+		assert_noop!(Erc20::claim_escrow(Origin::signed(CHARLIE), 0), MyError::<Test>::NotEscrowBeneficiary);
+		assert_ok!(Erc20::claim_escrow(Origin::signed(DAVE), 0));
+		assert_eq!(Erc20::free_balance(&DAVE), 100);
+
+		assert_ok!(Erc20::create_escrow(Origin::signed(BOB), DAVE, 100, 5));
+		assert_noop!(Erc20::refund_escrow(Origin::signed(BOB), 1), MyError::<Test>::EscrowNotExpired);
+		run_to_block(6);
+		assert_noop!(Erc20::claim_escrow(Origin::signed(DAVE), 1), MyError::<Test>::EscrowExpired);
+		assert_ok!(Erc20::refund_escrow(Origin::signed(BOB), 1));
+		assert_eq!(Erc20::free_balance(&BOB), 900);
+		assert_eq!(Erc20::get_earmarked(), 0);
+	});
+}
+
+#[test]
+fn scheduled_transfers_run_at_their_block() {
+	run_test(|| {
+		setup();
+		assert_ok!(Erc20::schedule_transfer(Origin::signed(BOB), DAVE, 100, 3));
+		assert_eq!(Erc20::reserved_balance(&BOB), 100);
+		assert_ok!(Erc20::schedule_transfer(Origin::signed(BOB), DAVE, 50, 5));
+		assert_noop!(Erc20::cancel_scheduled(Origin::signed(CHARLIE), 1), MyError::<Test>::NotScheduler);
+		assert_ok!(Erc20::cancel_scheduled(Origin::signed(BOB), 1));
+
+		run_to_block(3);
+		assert_eq!(Erc20::free_balance(&DAVE), 100);
+		assert_eq!(Erc20::reserved_balance(&BOB), 0);
+		assert!(has_event(TokenEvent::ScheduledExecuted(0)));
+
+		assert_ok!(Erc20::schedule_transfer(Origin::signed(BOB), DAVE, 10, 6));
+		assert_ok!(Erc20::schedule_transfer(Origin::signed(BOB), DAVE, 10, 6));
+		assert_noop!(Erc20::schedule_transfer(Origin::signed(BOB), DAVE, 10, 6), MyError::<Test>::TooManyScheduled);
+	});
+}
+
+#[test]
+fn a_halt_pushes_scheduled_transfers_back_within_the_per_block_cap() {
+	run_test(|| {
+		setup();
+		assert_ok!(Erc20::schedule_transfer(Origin::signed(BOB), DAVE, 10, 3));
+		assert_ok!(Erc20::schedule_transfer(Origin::signed(BOB), DAVE, 10, 3));
+		assert_ok!(Erc20::schedule_transfer(Origin::signed(BOB), DAVE, 10, 4));
+		assert_ok!(Erc20::schedule_transfer(Origin::signed(BOB), DAVE, 10, 4));
+		assert_ok!(Erc20::force_halt(Origin::root()));
+		run_to_block(3);
+		assert_eq!(Erc20::schedule_queue(4u64), vec![2, 3]);
+		assert_eq!(Erc20::schedule_queue(5u64), vec![0, 1]);
+		assert_eq!(Erc20::scheduled(0u64).map(|scheduled| scheduled.at), Some(5));
+
+		assert_ok!(Erc20::force_resume(Origin::root()));
+		run_to_block(5);
+		assert_eq!(Erc20::free_balance(&DAVE), 40);
+	});
+}
+
+#[test]
+fn subscriptions_are_collected_once_per_period() {
+	run_test(|| {
+		setup();
+		assert_ok!(Erc20::create_subscription(Origin::signed(BOB), CHARLIE, 10, 5, Some(2)));
+		assert_ok!(Erc20::collect(Origin::signed(CHARLIE), BOB, 0));
+		assert_noop!(Erc20::collect(Origin::signed(CHARLIE), BOB, 0), MyError::<Test>::PeriodNotElapsed);
+		assert_noop!(Erc20::collect(Origin::signed(DAVE), BOB, 0), MyError::<Test>::NotMerchant);
+		run_to_block(6);
+		assert_ok!(Erc20::collect(Origin::signed(CHARLIE), BOB, 0));
+		assert_eq!(Erc20::free_balance(&CHARLIE), 1_020);
+		assert!(Erc20::subscriptions(&BOB, 0u64).is_none());
+	});
+}
+
+#[test]
+fn streams_pay_out_as_they_accrue() {
+	run_test(|| {
+		setup();
+		assert_ok!(Erc20::create_stream(Origin::signed(BOB), DAVE, 100, 1, 11));
+		run_to_block(6);
+		assert_ok!(Erc20::withdraw_from_stream(Origin::signed(DAVE), 0));
+		assert_eq!(Erc20::free_balance(&DAVE), 50);
+		assert_noop!(Erc20::cancel_stream(Origin::signed(CHARLIE), 0), MyError::<Test>::NotStreamParty);
+		assert_ok!(Erc20::cancel_stream(Origin::signed(BOB), 0));
+		assert_eq!(Erc20::free_balance(&BOB), 950);
+		assert_eq!(Erc20::get_earmarked(), 0);
+	});
+}
+
+#[test]
+fn invoices_are_paid_by_their_payer_until_they_expire() {
+	run_test(|| {
+		setup();
+		assert_ok!(Erc20::create_invoice(Origin::signed(CHARLIE), Some(BOB), 30, b"inv".to_vec(), 10));
+		assert_noop!(Erc20::pay_invoice(Origin::signed(DAVE), 0), MyError::<Test>::NotInvoicePayer);
+		assert_ok!(Erc20::pay_invoice(Origin::signed(BOB), 0));
+		assert_eq!(Erc20::free_balance(&CHARLIE), 1_030);
+		assert!(Erc20::invoices(0u64).is_none());
+
+		assert_ok!(Erc20::create_invoice(Origin::signed(CHARLIE), None, 5, Vec::new(), 2));
+		run_to_block(3);
+		assert_noop!(Erc20::pay_invoice(Origin::signed(BOB), 1), MyError::<Test>::InvoiceExpired);
+	});
+}
+
+#[test]
+fn snapshots_keep_balances_as_they_were() {
+	run_test(|| {
+		setup();
+		assert_ok!(Erc20::take_snapshot(Origin::signed(ALICE)));
+		assert!(has_event(TokenEvent::SnapshotTaken(1, 1)));
+		assert_ok!(Erc20::transfer(Origin::signed(BOB), DAVE, 400));
+		assert_eq!(Erc20::balance_at_snapshot(1, &BOB), 1_000);
+		assert_eq!(Erc20::balance_at_snapshot(1, &DAVE), 0);
+		assert_eq!(Erc20::free_balance(&BOB), 600);
+	});
+}
+
+#[test]
+fn delegated_votes_follow_the_balance() {
+	run_test(|| {
+		setup();
+		assert_ok!(Erc20::delegate(Origin::signed(BOB), CHARLIE));
+		assert!(has_event(TokenEvent::DelegateChanged(BOB, BOB, CHARLIE)));
+		assert_eq!(Erc20::get_votes(&CHARLIE), 2_000);
+		assert_eq!(Erc20::get_votes(&BOB), 0);
+		run_to_block(3);
+		assert_ok!(Erc20::transfer(Origin::signed(BOB), DAVE, 400));
+		assert_eq!(Erc20::get_votes(&CHARLIE), 1_600);
+		assert_eq!(Erc20::get_past_votes(&CHARLIE, 2), 2_000);
+		assert_noop!(Erc20::delegate(Origin::signed(BOB), CHARLIE), MyError::<Test>::AlreadyDelegated);
+	});
+}
+
+#[test]
+fn staking_pays_rewards_per_block() {
+	run_test(|| {
+		setup();
+		assert_ok!(Erc20::set_staking_reward(Origin::signed(ALICE), 10));
+		assert_ok!(Erc20::stake(Origin::signed(BOB), 100));
+		assert_eq!(Erc20::free_balance(&BOB), 900);
+		assert_eq!(Erc20::total_staked(), 100);
+		run_to_block(6);
+		assert_ok!(Erc20::claim_rewards(Origin::signed(BOB)));
+		assert!(has_event(TokenEvent::RewardsClaimed(BOB, 50)));
+		assert_eq!(Erc20::free_balance(&BOB), 950);
+		assert_noop!(Erc20::claim_rewards(Origin::signed(BOB)), MyError::<Test>::NothingToWithdraw);
+
+		assert_noop!(Erc20::unstake(Origin::signed(BOB), 101), MyError::<Test>::NotEnoughStaked);
+		assert_ok!(Erc20::unstake(Origin::signed(BOB), 100));
+		assert_eq!(Erc20::free_balance(&BOB), 1_050);
+		assert_eq!(Erc20::total_staked(), 0);
+		assert_eq!(Erc20::get_earmarked(), 0);
+	});
+}
+
+#[test]
+fn staking_rewards_are_split_by_stake() {
+	run_test(|| {
+		setup();
+		assert_ok!(Erc20::set_staking_reward(Origin::signed(ALICE), 12));
+		assert_ok!(Erc20::stake(Origin::signed(BOB), 100));
+		assert_ok!(Erc20::stake(Origin::signed(CHARLIE), 200));
+		run_to_block(2);
+		assert_ok!(Erc20::claim_rewards(Origin::signed(BOB)));
+		assert_ok!(Erc20::claim_rewards(Origin::signed(CHARLIE)));
+		assert!(has_event(TokenEvent::RewardsClaimed(BOB, 4)));
+		assert!(has_event(TokenEvent::RewardsClaimed(CHARLIE, 8)));
+	});
+}
+
+#[test]
+fn unstaked_tokens_wait_for_the_unbonding_period() {
+	run_test(|| {
+		setup();
+		set_unbonding_period(3);
+		assert_ok!(Erc20::stake(Origin::signed(BOB), 100));
+		assert_ok!(Erc20::unstake(Origin::signed(BOB), 100));
+		assert_eq!(Erc20::unbonding(&BOB), Some((100, 4)));
+		assert_eq!(Erc20::free_balance(&BOB), 900);
+		assert_noop!(Erc20::withdraw_unbonded(Origin::signed(BOB)), MyError::<Test>::StillUnbonding);
+		run_to_block(4);
+		assert_ok!(Erc20::withdraw_unbonded(Origin::signed(BOB)));
+		assert_eq!(Erc20::free_balance(&BOB), 1_000);
+		assert_noop!(Erc20::withdraw_unbonded(Origin::signed(BOB)), MyError::<Test>::NothingToWithdraw);
+	});
+}
+
+#[test]
+fn a_failed_reward_payout_leaves_the_rewards_claimable() {
+	run_test(|| {
+		mint_token(100_000, 100);
+		assert_ok!(Erc20::transfer(Origin::signed(ALICE), BOB, 100));
+		assert_ok!(Erc20::set_staking_reward(Origin::signed(ALICE), 10));
+		assert_ok!(Erc20::stake(Origin::signed(BOB), 100));
+		run_to_block(3);
+		//BOB holds nothing, so 20 tokens would open an account below MinBalance
+		assert_noop!(Erc20::claim_rewards(Origin::signed(BOB)), MyError::<Test>::BelowMinimum);
+		run_to_block(11);
+		assert_ok!(Erc20::claim_rewards(Origin::signed(BOB)));
+		assert_eq!(Erc20::free_balance(&BOB), 100);
+	});
+}
+
+#[test]
+fn dividends_are_shared_pro_rata_among_holders() {
+	run_test(|| {
+		mint_token(1_000, 1);
+		assert_ok!(Erc20::transfer(Origin::signed(ALICE), BOB, 100));
+		assert_ok!(Erc20::transfer(Origin::signed(ALICE), CHARLIE, 300));
+		assert_ok!(Erc20::transfer(Origin::signed(ALICE), DAVE, 600));
+		assert_ok!(Erc20::fund_distribution(Origin::signed(DAVE), 600));
+		assert_eq!(Erc20::withdrawable_dividend_of(&BOB), 150);
+		assert_eq!(Erc20::withdrawable_dividend_of(&CHARLIE), 450);
+
+		assert_ok!(Erc20::claim_dividend(Origin::signed(BOB)));
+		assert_eq!(Erc20::free_balance(&BOB), 250);
+		assert_ok!(Erc20::transfer(Origin::signed(BOB), CHARLIE, 250));
+		assert_eq!(Erc20::withdrawable_dividend_of(&BOB), 0);
+		assert_eq!(Erc20::withdrawable_dividend_of(&CHARLIE), 450);
+		assert_noop!(Erc20::claim_dividend(Origin::signed(BOB)), MyError::<Test>::NothingToWithdraw);
+	});
+}
+
+#[test]
+fn a_successful_sale_pays_the_owner() {
+	run_test(|| {
+		setup();
+		assert_ok!(Erc20::start_sale(Origin::signed(ALICE), 2, 100, 500, 1, 5));
+		assert_ok!(Erc20::buy(Origin::signed(BOB), 200));
+		assert_eq!(Erc20::free_balance(&BOB), 1_400);
+		assert_eq!(Erc20::contributions(1u32, &BOB), (200, 400));
+		assert_noop!(Erc20::buy(Origin::signed(CHARLIE), 301), MyError::<Test>::HardCapExceeded);
+		assert_noop!(Erc20::finalize_sale(Origin::signed(ALICE)), MyError::<Test>::SaleNotEnded);
+		run_to_block(6);
+		assert_ok!(Erc20::finalize_sale(Origin::signed(ALICE)));
+		assert_eq!(NativeBalances::free_balance(&ALICE), 10_200);
+		assert_noop!(Erc20::claim_refund(Origin::signed(BOB)), MyError::<Test>::SoftCapReached);
+	});
+}
+
+#[test]
+fn a_failed_sale_refunds_its_contributors() {
+	run_test(|| {
+		setup();
+		assert_ok!(Erc20::start_sale(Origin::signed(ALICE), 2, 300, 500, 1, 5));
+		assert_ok!(Erc20::buy(Origin::signed(BOB), 100));
+		run_to_block(6);
+		assert_ok!(Erc20::finalize_sale(Origin::signed(ALICE)));
+		assert_noop!(Erc20::start_sale(Origin::signed(ALICE), 2, 100, 500, 6, 10), MyError::<Test>::SaleActive);
+		assert_ok!(Erc20::claim_refund(Origin::signed(BOB)));
+		assert_eq!(Erc20::free_balance(&BOB), 1_000);
+		assert_eq!(NativeBalances::free_balance(&BOB), 10_000);
+		assert_eq!(Erc20::contributions(1u32, &BOB), (0, 0));
+		assert_noop!(Erc20::claim_refund(Origin::signed(BOB)), MyError::<Test>::NoContribution);
+		assert_ok!(Erc20::start_sale(Origin::signed(ALICE), 2, 100, 500, 6, 10));
+	});
+}
+
+#[test]
+fn refunds_follow_what_the_buyer_still_holds() {
+	run_test(|| {
+		setup();
+		assert_ok!(Erc20::start_sale(Origin::signed(ALICE), 2, 300, 500, 1, 5));
+		assert_ok!(Erc20::buy(Origin::signed(BOB), 100));
+		assert_ok!(Erc20::transfer(Origin::signed(BOB), DAVE, 1_100));
+		run_to_block(6);
+		assert_ok!(Erc20::finalize_sale(Origin::signed(ALICE)));
+		assert_ok!(Erc20::claim_refund(Origin::signed(BOB)));
+		assert!(has_event(TokenEvent::Refunded(BOB, 50, 100)));
+		assert_eq!(NativeBalances::free_balance(&BOB), 9_950);
+		assert_eq!(Erc20::contributions(1u32, &BOB), (50, 100));
+		assert_noop!(Erc20::claim_refund(Origin::signed(BOB)), MyError::<Test>::NotEnoughFunds);
+
+		assert_ok!(Erc20::transfer(Origin::signed(DAVE), BOB, 100));
+		assert_ok!(Erc20::claim_refund(Origin::signed(BOB)));
+		assert_eq!(NativeBalances::free_balance(&BOB), 10_000);
+		assert_eq!(Erc20::contributions(1u32, &BOB), (0, 0));
+	});
+}
+
+#[test]
+fn wrapped_tokens_are_backed_by_native_currency() {
+	run_test(|| {
+		setup();
+		assert_ok!(Erc20::wrap(Origin::signed(BOB), 100));
+		assert_eq!(NativeBalances::free_balance(&BOB), 9_900);
+		assert_eq!(Erc20::free_balance(&BOB), 1_100);
+		assert_eq!(Erc20::wrapped_supply(), 100);
+		assert_ok!(Erc20::unwrap(Origin::signed(BOB), 40));
+		assert_eq!(NativeBalances::free_balance(&BOB), 9_940);
+		assert_noop!(Erc20::unwrap(Origin::signed(BOB), 61), MyError::<Test>::InsufficientBacking);
+	});
+}
+
+#[test]
+fn collateralized_positions_release_collateral_as_they_are_redeemed() {
+	run_test(|| {
+		setup();
+		assert_noop!(Erc20::mint_collateralized(Origin::signed(BOB), 100), MyError::<Test>::InvalidCollateralRatio);
+		assert_ok!(Erc20::set_collateral_ratio(Origin::signed(ALICE), 15_000));
+		assert_ok!(Erc20::mint_collateralized(Origin::signed(BOB), 100));
+		assert_eq!(NativeBalances::reserved_balance(&BOB), 150);
+		assert_eq!(Erc20::free_balance(&BOB), 1_100);
+		assert_ok!(Erc20::redeem(Origin::signed(BOB), 40));
+		assert_eq!(NativeBalances::reserved_balance(&BOB), 90);
+		assert_noop!(Erc20::redeem(Origin::signed(BOB), 61), MyError::<Test>::ExceedsPosition);
+		assert_ok!(Erc20::redeem(Origin::signed(BOB), 60));
+		assert_eq!(NativeBalances::reserved_balance(&BOB), 0);
+		assert!(Erc20::collateral_positions(&BOB).is_none());
+	});
+}
+
+#[test]
+fn swaps_exchange_tokens_for_native_currency() {
+	run_test(|| {
+		setup();
+		assert_ok!(Erc20::create_swap(Origin::signed(BOB), CHARLIE, 50, 500, 10));
+		assert_eq!(Erc20::reserved_balance(&BOB), 50);
+		assert_noop!(Erc20::accept_swap(Origin::signed(DAVE), 0), MyError::<Test>::NotSwapCounterparty);
+		assert_ok!(Erc20::accept_swap(Origin::signed(CHARLIE), 0));
+		assert_eq!(Erc20::free_balance(&BOB), 950);
+		assert_eq!(Erc20::free_balance(&CHARLIE), 1_050);
+		assert_eq!(NativeBalances::free_balance(&BOB), 10_500);
+		assert_eq!(NativeBalances::free_balance(&CHARLIE), 9_500);
+
+		assert_ok!(Erc20::create_swap(Origin::signed(BOB), CHARLIE, 50, 500, 10));
+		assert_noop!(Erc20::cancel_swap(Origin::signed(DAVE), 1), MyError::<Test>::NotSwapCreator);
+		assert_ok!(Erc20::cancel_swap(Origin::signed(BOB), 1));
+		assert_eq!(Erc20::reserved_balance(&BOB), 0);
+	});
+}
+
+#[test]
+fn rebases_scale_every_balance() {
+	run_test(|| {
+		setup();
+		assert_noop!(Erc20::rebase(Origin::signed(BOB), 2, 1), MyError::<Test>::NotOwner);
+		assert_noop!(Erc20::rebase(Origin::signed(ALICE), 0, 1), MyError::<Test>::InvalidRebase);
+		assert_ok!(Erc20::rebase(Origin::signed(ALICE), 2, 1));
+		assert_eq!(Erc20::free_balance(&BOB), 2_000);
+		assert_eq!(Erc20::free_balance(&ALICE), 196_000);
+		assert_eq!(Erc20::get_total_issuance(), 200_000);
+		assert_ok!(Erc20::transfer(Origin::signed(BOB), DAVE, 2_000));
+		assert_eq!(Erc20::free_balance(&DAVE), 2_000);
+
+		assert_ok!(Erc20::rebase(Origin::signed(ALICE), 1, 4));
+		assert_eq!(Erc20::free_balance(&DAVE), 500);
+		assert_eq!(Erc20::get_total_issuance(), 50_000);
+	});
+}
+
+#[test]
+fn rebases_leave_what_the_pallet_account_owes_untouched() {
+	run_test(|| {
+		setup();
+		assert_ok!(Erc20::create_escrow(Origin::signed(BOB), DAVE, 100, 10));
+		assert_ok!(Erc20::rebase(Origin::signed(ALICE), 1, 2));
+		assert_eq!(Erc20::free_balance(&Erc20::account_id()), 100);
+		assert_eq!(Erc20::free_balance(&BOB), 450);
+		assert_eq!(Erc20::get_total_issuance(), 50_050);
+		assert_ok!(Erc20::claim_escrow(Origin::signed(DAVE), 0));
+		assert_eq!(Erc20::free_balance(&DAVE), 100);
+
+		assert_ok!(Erc20::create_escrow(Origin::signed(CHARLIE), DAVE, 100, 10));
+		assert_ok!(Erc20::rebase(Origin::signed(ALICE), 4, 1));
+		assert_eq!(Erc20::free_balance(&Erc20::account_id()), 100);
+		assert_noop!(Erc20::recover_stranded(Origin::root(), ALICE, 1), MyError::<Test>::NothingToRecover);
+		assert_ok!(Erc20::claim_escrow(Origin::signed(DAVE), 1));
+		assert!(has_event(TokenEvent::EscrowClaimed(1, DAVE, 100)));
+		assert_eq!(Erc20::free_balance(&Erc20::account_id()), 0);
+	});
+}
+
+#[test]
+fn the_faucet_drips_from_its_source_with_a_cooldown() {
+	run_test(|| {
+		setup();
+		assert_noop!(Erc20::faucet(Origin::signed(DAVE)), MyError::<Test>::FaucetDisabled);
+		assert_ok!(Erc20::approve(Origin::signed(ALICE), Erc20::account_id(), 1_000));
+		let config = FaucetConfig { drip_amount: 10, cooldown_blocks: 5, source: ALICE };
+		assert_ok!(Erc20::set_faucet_config(Origin::signed(ALICE), Some(config)));
+		assert_ok!(Erc20::faucet(Origin::signed(DAVE)));
+		assert_eq!(Erc20::free_balance(&DAVE), 10);
+		assert_noop!(Erc20::faucet(Origin::signed(DAVE)), MyError::<Test>::FaucetCooldown);
+		run_to_block(6);
+		assert_ok!(Erc20::faucet(Origin::signed(DAVE)));
+		assert_eq!(Erc20::free_balance(&DAVE), 20);
+		assert_eq!(Erc20::allowances(&ALICE, &Erc20::account_id()).map(|allowance| allowance.amount), Some(980));
+	});
+}
+
+#[test]
+fn airdrops_merge_repeated_recipients() {
+	run_test(|| {
+		setup();
+		assert_ok!(Erc20::airdrop(Origin::signed(BOB), vec![(CHARLIE, 10), (DAVE, 20), (CHARLIE, 5)]));
+		assert!(has_event(TokenEvent::AirdropCompleted(2, 35)));
+		assert_eq!(Erc20::free_balance(&CHARLIE), 1_015);
+		assert_eq!(Erc20::free_balance(&DAVE), 20);
+		assert_noop!(Erc20::airdrop(Origin::signed(BOB), vec![(DAVE, 1); 4]), MyError::<Test>::AirdropTooLarge);
+		assert_noop!(Erc20::airdrop(Origin::signed(BOB), vec![(DAVE, 2_000)]), MyError::<Test>::NotEnoughFunds);
+	});
+}
+
+#[test]
+fn claim_drops_pay_each_proven_leaf_once() {
+	run_test(|| {
+		setup();
+		let (bob_leaf, charlie_leaf) = (claim_leaf(BOB, 100), claim_leaf(CHARLIE, 50));
+		let root = hash_pair(bob_leaf, charlie_leaf);
+		assert_ok!(Erc20::create_claim_drop(Origin::signed(ALICE), root, 150, 10));
+		assert_eq!(Erc20::get_earmarked(), 150);
+
+		assert_noop!(Erc20::claim(Origin::signed(BOB), 0, 101, vec![charlie_leaf]), MyError::<Test>::InvalidProof);
+		assert_ok!(Erc20::claim(Origin::signed(BOB), 0, 100, vec![charlie_leaf]));
+		assert_eq!(Erc20::free_balance(&BOB), 1_100);
+		assert_noop!(Erc20::claim(Origin::signed(BOB), 0, 100, vec![charlie_leaf]), MyError::<Test>::AlreadyClaimed);
+
+		assert_noop!(Erc20::sweep_claim_drop(Origin::signed(ALICE), 0, 1), MyError::<Test>::DropNotExpired);
+		run_to_block(11);
+		assert_noop!(Erc20::claim(Origin::signed(CHARLIE), 0, 50, vec![bob_leaf]), MyError::<Test>::DropExpired);
+		assert_ok!(Erc20::sweep_claim_drop(Origin::signed(ALICE), 0, 0));
+		assert_eq!(Erc20::get_earmarked(), 0);
+		assert!(has_event(TokenEvent::ClaimRecordsCleared(0, 0)));
+		assert!(Erc20::claimed(0u64, BOB));
+		assert_ok!(Erc20::sweep_claim_drop(Origin::signed(DAVE), 0, 1));
+		assert!(has_event(TokenEvent::ClaimRecordsCleared(0, 1)));
+		assert_noop!(Erc20::sweep_claim_drop(Origin::signed(DAVE), 0, 1), MyError::<Test>::UnknownDrop);
+	});
+}
+
+#[test]
+fn ethereum_claims_need_a_signature_over_the_claimer() {
+	run_test(|| {
+		setup();
+		let secret = secp256k1::SecretKey::parse(&[7u8; 32]).unwrap();
+		let public = secp256k1::PublicKey::from_secret_key(&secret);
+		let mut address = [0u8; 20];
+		address.copy_from_slice(&keccak_256(&public.serialize()[1..65])[12..]);
+		let sign = |who: u128| {
+			let message = secp256k1::Message::parse(&keccak_256(&Erc20::eth_claim_message(&who)));
+			let (signature, recovery_id) = secp256k1::sign(&message, &secret);
+			let mut eth_signature = [0u8; 65];
+			eth_signature[..64].copy_from_slice(&signature.serialize());
+			eth_signature[64] = recovery_id.serialize();
+			eth_signature
+		};
+
+		assert_ok!(Erc20::set_eth_claim(Origin::root(), address, Some(500)));
+		assert!(Erc20::claim_eth(Origin::signed(CHARLIE), sign(DAVE)).is_err());
+		assert_ok!(Erc20::claim_eth(Origin::signed(DAVE), sign(DAVE)));
+		assert!(has_event(TokenEvent::EthClaimed(DAVE, address, 500)));
+		assert_eq!(Erc20::free_balance(&DAVE), 500);
+		assert_noop!(Erc20::claim_eth(Origin::signed(DAVE), sign(DAVE)), MyError::<Test>::NoEthClaim);
+	});
+}
+
+#[test]
+fn imports_load_balances_before_transfers_open() {
+	run_test(|| {
+		setup();
+		assert_ok!(Erc20::open_import(Origin::root()));
+		assert_ok!(Erc20::force_import_balances(Origin::root(), vec![(10, 100), (11, 200)]));
+		assert_noop!(Erc20::transfer(Origin::signed(BOB), DAVE, 10), MyError::<Test>::ImportIsOpen);
+		assert_noop!(Erc20::force_import_balances(Origin::root(), vec![(10, 5)]), MyError::<Test>::DuplicateImport);
+		assert_noop!(
+			Erc20::force_import_balances(Origin::root(), vec![(12, 1), (13, 1), (14, 1), (15, 1)]),
+			MyError::<Test>::BatchTooLarge
+		);
+		assert_ok!(Erc20::close_import(Origin::root()));
+		assert!(has_event(TokenEvent::ImportClosed(300)));
+		assert_eq!(Erc20::free_balance(&11), 200);
+		assert_ok!(Erc20::transfer(Origin::signed(BOB), DAVE, 10));
+	});
+}
+
+#[test]
+fn conviction_locks_boost_votes_until_released() {
+	run_test(|| {
+		setup();
+		assert_noop!(Erc20::lock_for_conviction(Origin::signed(BOB), 100, 0), MyError::<Test>::InvalidConviction);
+		assert_ok!(Erc20::lock_for_conviction(Origin::signed(BOB), 100, 2));
+		assert_eq!(Erc20::get_votes(&BOB), 1_200);
+		assert_noop!(Erc20::transfer(Origin::signed(BOB), DAVE, 901), MyError::<Test>::LiquidityRestrictions);
+		assert_noop!(Erc20::release_conviction_lock(Origin::signed(BOB)), MyError::<Test>::ConvictionNotExpired);
+		run_to_block(21);
+		assert_ok!(Erc20::release_conviction_lock(Origin::signed(BOB)));
+		assert!(has_event(TokenEvent::ConvictionReleased(BOB, 100)));
+		assert_eq!(Erc20::get_votes(&BOB), 1_000);
+		assert_ok!(Erc20::transfer(Origin::signed(BOB), DAVE, 1_000));
+	});
+}
+
+#[test]
+fn dust_goes_to_the_treasury_or_is_burned() {
+	run_test(|| {
+		mint_token(100_000, 10);
+		assert_ok!(Erc20::transfer(Origin::signed(ALICE), BOB, 15));
+		assert_ok!(Erc20::transfer(Origin::signed(BOB), CHARLIE, 10));
+		assert_eq!(Erc20::free_balance(&BOB), 0);
+		assert_eq!(Erc20::free_balance(&Erc20::treasury_account()), 5);
+		assert!(has_event(TokenEvent::DustLost(BOB, 5)));
+
+		assert_ok!(Erc20::set_dust_policy(Origin::signed(ALICE), DustPolicy::Burn));
+		assert_ok!(Erc20::transfer(Origin::signed(ALICE), DAVE, 15));
+		assert_ok!(Erc20::transfer(Origin::signed(DAVE), CHARLIE, 10));
+		assert_eq!(Erc20::free_balance(&DAVE), 0);
+		assert_eq!(Erc20::get_total_issuance(), 99_995);
+	});
+}
+
+#[test]
+fn new_accounts_need_the_min_balance_which_can_only_be_lowered() {
+	run_test(|| {
+		mint_token(100_000, 10);
+		assert_noop!(Erc20::transfer(Origin::signed(ALICE), BOB, 9), MyError::<Test>::BelowMinimum);
+		assert_ok!(Erc20::transfer(Origin::signed(ALICE), BOB, 10));
+		assert_noop!(Erc20::set_min_balance(Origin::signed(ALICE), 20), MyError::<Test>::MinBalanceRaised);
+		assert_ok!(Erc20::set_min_balance(Origin::signed(ALICE), 5));
+		assert_ok!(Erc20::transfer(Origin::signed(ALICE), CHARLIE, 5));
+	});
+}
+
+#[test]
+fn touched_accounts_take_any_amount_and_refund_their_deposit() {
+	run_test(|| {
+		mint_token(100_000, 10);
+		assert_noop!(Erc20::transfer(Origin::signed(ALICE), 7, 5), MyError::<Test>::BelowMinimum);
+		assert_ok!(Erc20::touch(Origin::signed(BOB), 7));
+		assert_eq!(NativeBalances::reserved_balance(&BOB), 10);
+		assert_eq!(System::account(&7).providers, 1);
+		assert_noop!(Erc20::touch(Origin::signed(BOB), 7), MyError::<Test>::AlreadyTouched);
+
+		assert_ok!(Erc20::transfer(Origin::signed(ALICE), 7, 5));
+		assert_eq!(Erc20::free_balance(&7), 5);
+		assert_noop!(Erc20::refund_touch(Origin::signed(BOB), 7), MyError::<Test>::AccountNotEmpty);
+		assert_ok!(Erc20::transfer_all(Origin::signed(7), ALICE));
+		assert_noop!(Erc20::refund_touch(Origin::signed(CHARLIE), 7), MyError::<Test>::NotTouchParty);
+		assert_ok!(Erc20::refund_touch(Origin::signed(7), 7));
+		assert!(has_event(TokenEvent::TouchRefunded(7, BOB, 10)));
+		assert_eq!(NativeBalances::reserved_balance(&BOB), 0);
+		assert_eq!(System::account(&7).providers, 0);
+		assert_noop!(Erc20::refund_touch(Origin::signed(BOB), 7), MyError::<Test>::NotTouched);
+	});
+}